@@ -0,0 +1,108 @@
+use anyhow::anyhow;
+use common::input::{self, InputKey, KEY_VARIABLE};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Manages the personal puzzle inputs in the current directory.
+///
+/// Usage:
+///   `inputs check`              fails if a plain text input is tracked by git
+///   `inputs encrypt [--remove]` writes `<input>.enc` for every plain input
+///   `inputs decrypt`            restores plain inputs from `<input>.enc`
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["check"] => check(),
+        ["encrypt"] => encrypt(false),
+        ["encrypt", "--remove"] => encrypt(true),
+        ["decrypt"] => decrypt(),
+        _ => Err(anyhow!(
+            "Usage: inputs check | encrypt [--remove] | decrypt"
+        )),
+    }
+}
+
+fn check() -> anyhow::Result<()> {
+    let output = Command::new("git").args(["ls-files", "-z"]).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git ls-files failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let tracked: Vec<String> = String::from_utf8(output.stdout)?
+        .split('\0')
+        .filter(|path| {
+            Path::new(path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(input::is_input_file_name)
+        })
+        .map(|path| path.to_string())
+        .collect();
+    if tracked.is_empty() {
+        println!("No plain text inputs are tracked.");
+        return Ok(());
+    }
+    for path in tracked.iter() {
+        eprintln!("tracked plain text input: {path}");
+    }
+    Err(anyhow!(
+        "{} plain text input(s) are tracked; encrypt them and remove them with `git rm --cached`",
+        tracked.len()
+    ))
+}
+
+fn encrypt(remove: bool) -> anyhow::Result<()> {
+    let key = require_key()?;
+    for path in input_files(".", input::is_input_file_name)? {
+        let contents = std::fs::read_to_string(&path)?;
+        std::fs::write(
+            input::encrypted_path(&path),
+            input::encrypt(&contents, &key)?,
+        )?;
+        if remove {
+            std::fs::remove_file(&path)?;
+        }
+        println!("encrypted {}", path.display());
+    }
+    Ok(())
+}
+
+fn decrypt() -> anyhow::Result<()> {
+    let key = require_key()?;
+    let suffix = format!(".txt.{}", input::ENCRYPTED_EXTENSION);
+    for path in input_files(".", |name| {
+        name.starts_with("everybody_codes_") && name.ends_with(&suffix)
+    })? {
+        let plain_path = path.with_extension("");
+        let contents = input::decrypt(&std::fs::read(&path)?, &key)?;
+        std::fs::write(&plain_path, contents)?;
+        println!("decrypted {}", plain_path.display());
+    }
+    Ok(())
+}
+
+fn require_key() -> anyhow::Result<InputKey> {
+    input::key_from_env().ok_or_else(|| anyhow!("{KEY_VARIABLE} is not set"))
+}
+
+fn input_files(
+    dir: impl AsRef<Path>,
+    filter: impl Fn(&str) -> bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut result = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(&filter)
+        {
+            result.push(path);
+        }
+    }
+    result.sort();
+    Ok(result)
+}
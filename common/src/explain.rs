@@ -0,0 +1,76 @@
+//! Optional, human readable traces of how a part arrived at its answer.
+//!
+//! Solutions record steps with [`Explain::step`]. The closure is only called
+//! when tracing is enabled, so solutions can trace freely without slowing down
+//! normal runs.
+
+pub const EXPLAIN_FLAG: &str = "--explain";
+
+#[derive(Debug, Default)]
+pub struct Explain {
+    enabled: bool,
+    lines: Vec<String>,
+}
+
+impl Explain {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(false)
+    }
+
+    /// Enables tracing if the program was started with `--explain`.
+    pub fn from_args() -> Self {
+        Self::new(std::env::args().skip(1).any(|arg| arg == EXPLAIN_FLAG))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn step(&mut self, line: impl FnOnce() -> String) {
+        if self.enabled {
+            self.lines.push(line());
+        }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Prints the collected steps under `title` and starts a new trace.
+    pub fn print(&mut self, title: &str) {
+        if !self.enabled {
+            return;
+        }
+        println!("--- {title} ---");
+        for line in self.lines.drain(..) {
+            println!("{line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_skips_steps() {
+        let mut explain = Explain::disabled();
+        explain.step(|| panic!("must not be called"));
+        assert!(explain.lines().is_empty());
+    }
+
+    #[test]
+    fn test_enabled_records_steps() {
+        let mut explain = Explain::new(true);
+        explain.step(|| "first".to_string());
+        explain.step(|| format!("second {}", 2));
+        assert_eq!(explain.lines(), ["first", "second 2"]);
+    }
+}
@@ -0,0 +1,307 @@
+//! Integer points and vectors, directions, rectangles and circle helpers.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan_distance(&self, other: &Point) -> i64 {
+        (*other - *self).manhattan_length()
+    }
+
+    /// The four orthogonally adjacent points.
+    pub fn neighbors4(&self) -> impl Iterator<Item = Point> + use<> {
+        let p = *self;
+        Direction::ALL.into_iter().map(move |d| p + d.vector())
+    }
+
+    /// The eight points surrounding this one, diagonals included.
+    pub fn neighbors8(&self) -> impl Iterator<Item = Point> + use<> {
+        let p = *self;
+        Vector::AROUND.into_iter().map(move |v| p + v)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vector {
+    pub dx: i64,
+    pub dy: i64,
+}
+
+impl Vector {
+    /// Offsets of the eight surrounding cells in reading order.
+    pub const AROUND: [Vector; 8] = [
+        Vector::new(-1, -1),
+        Vector::new(0, -1),
+        Vector::new(1, -1),
+        Vector::new(-1, 0),
+        Vector::new(1, 0),
+        Vector::new(-1, 1),
+        Vector::new(0, 1),
+        Vector::new(1, 1),
+    ];
+
+    pub const fn new(dx: i64, dy: i64) -> Self {
+        Self { dx, dy }
+    }
+
+    pub fn manhattan_length(&self) -> i64 {
+        self.dx.abs() + self.dy.abs()
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.dx, self.y + v.dy)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Point {
+        Point::new(self.x - v.dx, self.y - v.dy)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: i64) -> Vector {
+        Vector::new(self.dx * n, self.dy * n)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.dx, -self.dy)
+    }
+}
+
+/// Grid directions with `y` growing downwards, as in the puzzle texts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn vector(&self) -> Vector {
+        match self {
+            Direction::Up => Vector::new(0, -1),
+            Direction::Right => Vector::new(1, 0),
+            Direction::Down => Vector::new(0, 1),
+            Direction::Left => Vector::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.turn_right().turn_right()
+    }
+}
+
+/// An axis aligned rectangle including both corners.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    pub fn new(a: Point, b: Point) -> Self {
+        Self {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.x - self.min.x + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.y - self.min.y + 1
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// The points both rectangles contain, if there are any.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let min = Point::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Point::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        (min.x <= max.x && min.y <= max.y).then_some(Rect { min, max })
+    }
+
+    /// All points of the rectangle row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<> {
+        self.points_step(1)
+    }
+
+    /// Every `step`-th point in both directions, starting at `min`, row by row.
+    pub fn points_step(&self, step: i64) -> impl Iterator<Item = Point> + use<> {
+        assert!(step > 0, "step must be positive");
+        let Rect { min, max } = *self;
+        (min.y..=max.y).step_by(step as usize).flat_map(move |y| {
+            (min.x..=max.x)
+                .step_by(step as usize)
+                .map(move |x| Point::new(x, y))
+        })
+    }
+}
+
+/// Checks whether the chords `a` and `b` between labelled points on a circle
+/// cross each other.
+///
+/// The labels have to increase around the circle. Chords sharing an endpoint
+/// do not cross.
+pub fn chords_cross<T: Ord + Copy>(a: (T, T), b: (T, T)) -> bool {
+    let (a1, a2) = (a.0.min(a.1), a.0.max(a.1));
+    let (b1, b2) = (b.0.min(b.1), b.0.max(b.1));
+    if a1 == b1 || a1 == b2 || a2 == b1 || a2 == b2 {
+        return false;
+    }
+    (a1 < b1 && b1 < a2) != (a1 < b2 && b2 < a2)
+}
+
+/// Position of point `label` of `count` points spread evenly on a circle,
+/// starting at the top and going clockwise.
+pub fn circle_position(label: usize, count: usize, radius: f64) -> (f64, f64) {
+    let angle = std::f64::consts::TAU * label as f64 / count as f64;
+    (radius * angle.sin(), -radius * angle.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let p = Point::new(1, 2) + Vector::new(3, -4) * 2;
+        assert_eq!(p, Point::new(7, -6));
+        assert_eq!(p - Point::new(1, 2), Vector::new(6, -8));
+        assert_eq!(p.manhattan_distance(&Point::new(1, 2)), 14);
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Point::new(0, 0).neighbors4().count(), 4);
+        assert_eq!(Point::new(0, 0).neighbors8().count(), 8);
+    }
+
+    #[test]
+    fn test_rect_points_step() {
+        let rect = Rect::new(Point::new(10, 5), Point::new(0, 0));
+        let points: Vec<Point> = rect.points_step(5).collect();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0, 0),
+                Point::new(5, 0),
+                Point::new(10, 0),
+                Point::new(0, 5),
+                Point::new(5, 5),
+                Point::new(10, 5),
+            ]
+        );
+        assert_eq!(rect.points().count() as i64, rect.width() * rect.height());
+    }
+
+    #[test]
+    fn test_rect_intersection() {
+        let rect = Rect::new(Point::new(0, 0), Point::new(10, 5));
+        let other = Rect::new(Point::new(8, -3), Point::new(i64::MAX, 2));
+        assert_eq!(
+            rect.intersection(&other),
+            Some(Rect::new(Point::new(8, 0), Point::new(10, 2)))
+        );
+        let apart = Rect::new(Point::new(11, 0), Point::new(12, 5));
+        assert_eq!(rect.intersection(&apart), None);
+    }
+
+    #[test]
+    fn test_chords_cross() {
+        assert!(chords_cross((1, 5), (2, 6)));
+        assert!(chords_cross((5, 1), (6, 2)));
+        assert!(!chords_cross((1, 5), (2, 4)));
+        assert!(!chords_cross((1, 5), (5, 8)));
+    }
+
+    #[test]
+    fn test_circle_position() {
+        let (x, y) = circle_position(2, 8, 1.0);
+        assert!((x - 1.0).abs() < 1e-9 && y.abs() < 1e-9);
+    }
+}
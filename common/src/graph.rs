@@ -0,0 +1,411 @@
+//! Graph abstractions and the usual search algorithms on top of them.
+//!
+//! A graph is anything that can list the neighbors of a node. Explicit graphs
+//! are stored as an [`AdjacencyList`], graphs that are cheaper to compute on
+//! the fly can be described by a closure with [`ImplicitGraph`].
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    fn neighbors(&self, node: &Self::Node) -> impl Iterator<Item = Self::Node>;
+}
+
+/// A directed graph stored as a map from each node to its successors.
+#[derive(Debug, Clone)]
+pub struct AdjacencyList<N> {
+    adj: BTreeMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Ord> AdjacencyList<N> {
+    pub fn new() -> Self {
+        Self {
+            adj: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.adj.entry(node).or_default();
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_node(to.clone());
+        self.adj.entry(from).or_default().push(to);
+    }
+
+    pub fn add_undirected_edge(&mut self, a: N, b: N) {
+        self.add_edge(a.clone(), b.clone());
+        self.add_edge(b, a);
+    }
+
+    pub fn contains_edge(&self, from: &N, to: &N) -> bool {
+        self.adj.get(from).is_some_and(|next| next.contains(to))
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.adj.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+}
+
+impl<N: Clone + Ord> Default for AdjacencyList<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Clone + Ord> FromIterator<(N, Vec<N>)> for AdjacencyList<N> {
+    fn from_iter<T: IntoIterator<Item = (N, Vec<N>)>>(iter: T) -> Self {
+        let mut result = Self::new();
+        for (from, next) in iter {
+            result.add_node(from.clone());
+            for to in next {
+                result.add_edge(from.clone(), to);
+            }
+        }
+        result
+    }
+}
+
+impl<N: Clone + Ord + Hash> Graph for AdjacencyList<N> {
+    type Node = N;
+
+    fn neighbors(&self, node: &N) -> impl Iterator<Item = N> {
+        self.adj.get(node).into_iter().flatten().cloned()
+    }
+}
+
+/// A graph whose edges are computed by a closure.
+pub struct ImplicitGraph<N, F> {
+    successors: F,
+    node: PhantomData<fn(&N)>,
+}
+
+impl<N, F> ImplicitGraph<N, F> {
+    pub fn new(successors: F) -> Self {
+        Self {
+            successors,
+            node: PhantomData,
+        }
+    }
+}
+
+impl<N, F, I> Graph for ImplicitGraph<N, F>
+where
+    N: Clone + Eq + Hash,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Node = N;
+
+    fn neighbors(&self, node: &N) -> impl Iterator<Item = N> {
+        (self.successors)(node).into_iter()
+    }
+}
+
+/// Returns all nodes reachable from `starts` in breadth first order.
+pub fn bfs<G: Graph>(graph: &G, starts: impl IntoIterator<Item = G::Node>) -> Vec<G::Node> {
+    bfs_distances(graph, starts)
+        .into_iter()
+        .map(|(node, _)| node)
+        .collect()
+}
+
+/// Returns all nodes reachable from `starts` in breadth first order together
+/// with their distance to the nearest start node.
+pub fn bfs_distances<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+) -> Vec<(G::Node, usize)> {
+    let mut visited = HashSet::new();
+    let mut q = VecDeque::new();
+    for start in starts {
+        if visited.insert(start.clone()) {
+            q.push_back((start, 0));
+        }
+    }
+    let mut result = Vec::new();
+    while let Some((current, dist)) = q.pop_front() {
+        for next in graph.neighbors(&current) {
+            if visited.insert(next.clone()) {
+                q.push_back((next, dist + 1));
+            }
+        }
+        result.push((current, dist));
+    }
+    result
+}
+
+/// Finds a shortest path from `start` to a node satisfying `is_goal`.
+///
+/// The returned path includes both `start` and the goal node.
+pub fn bfs_path<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+) -> Option<Vec<G::Node>> {
+    let mut parents: HashMap<G::Node, Option<G::Node>> = HashMap::from([(start.clone(), None)]);
+    let mut q = VecDeque::from([start]);
+    while let Some(current) = q.pop_front() {
+        if is_goal(&current) {
+            let mut path = vec![current.clone()];
+            let mut node = current;
+            while let Some(Some(parent)) = parents.get(&node) {
+                path.push(parent.clone());
+                node = parent.clone();
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in graph.neighbors(&current) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(current.clone()));
+                q.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Groups `nodes` into the sets of nodes connected to each other.
+///
+/// The graph is expected to be undirected, i.e. contain every edge in both
+/// directions. Components are returned in the order of their first node in
+/// `nodes`, each of them in breadth first order.
+pub fn connected_components<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Vec<Vec<G::Node>> {
+    let mut visited = HashSet::new();
+    let mut result = Vec::new();
+    for node in nodes {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        let mut q = VecDeque::from([node]);
+        let mut component = Vec::new();
+        while let Some(current) = q.pop_front() {
+            for next in graph.neighbors(&current) {
+                if visited.insert(next.clone()) {
+                    q.push_back(next);
+                }
+            }
+            component.push(current);
+        }
+        result.push(component);
+    }
+    result
+}
+
+/// Enumerates all walks starting at `start` with at most `max_edges` edges.
+///
+/// Nodes may be visited more than once; the walk consisting of `start` alone
+/// is included.
+pub fn walks<G: Graph>(graph: &G, start: G::Node, max_edges: usize) -> Vec<Vec<G::Node>> {
+    let mut result = Vec::new();
+    let mut stack = vec![vec![start]];
+    while let Some(walk) = stack.pop() {
+        if walk.len() <= max_edges {
+            let last = walk.last().expect("walks are never empty");
+            for next in graph.neighbors(last) {
+                let mut longer = walk.clone();
+                longer.push(next);
+                stack.push(longer);
+            }
+        }
+        result.push(walk);
+    }
+    result
+}
+
+/// Returns the shortest distances from `start` to every reachable node.
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    cost: impl Fn(&G::Node, &G::Node) -> u64,
+) -> HashMap<G::Node, u64>
+where
+    G::Node: Ord,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut q = BinaryHeap::from([Reverse((0, start))]);
+    while let Some(Reverse((d, current))) = q.pop() {
+        if dist.get(&current).is_some_and(|best| *best < d) {
+            continue;
+        }
+        for next in graph.neighbors(&current) {
+            let next_dist = d + cost(&current, &next);
+            if dist.get(&next).is_none_or(|best| next_dist < *best) {
+                dist.insert(next.clone(), next_dist);
+                q.push(Reverse((next_dist, next)));
+            }
+        }
+    }
+    dist
+}
+
+/// Orders `nodes` so that every edge points from an earlier to a later node.
+///
+/// Returns `None` if the graph restricted to `nodes` contains a cycle.
+pub fn topological_sort<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Option<Vec<G::Node>> {
+    let nodes: Vec<G::Node> = nodes.into_iter().collect();
+    let mut in_degree: HashMap<G::Node, usize> = nodes.iter().map(|n| (n.clone(), 0)).collect();
+    for node in nodes.iter() {
+        for next in graph.neighbors(node) {
+            if let Some(d) = in_degree.get_mut(&next) {
+                *d += 1;
+            }
+        }
+    }
+    let mut q: VecDeque<G::Node> = nodes
+        .iter()
+        .filter(|n| in_degree[*n] == 0)
+        .cloned()
+        .collect();
+    let mut result = Vec::with_capacity(nodes.len());
+    while let Some(current) = q.pop_front() {
+        for next in graph.neighbors(&current) {
+            if let Some(d) = in_degree.get_mut(&next) {
+                *d -= 1;
+                if *d == 0 {
+                    q.push_back(next);
+                }
+            }
+        }
+        result.push(current);
+    }
+    (result.len() == nodes.len()).then_some(result)
+}
+
+/// Disjoint sets over the indices `0..len`.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Merges the sets of `a` and `b`. Returns `false` if they were already joined.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        true
+    }
+
+    pub fn set_size(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> AdjacencyList<i32> {
+        let mut g = AdjacencyList::new();
+        g.add_undirected_edge(1, 2);
+        g.add_undirected_edge(2, 3);
+        g.add_undirected_edge(4, 5);
+        g.add_node(6);
+        g
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let g = sample();
+        let result = bfs_distances(&g, [1]);
+        assert_eq!(result, vec![(1, 0), (2, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn test_bfs_path() {
+        let g = ImplicitGraph::new(|n: &u32| [n + 1, n * 3]);
+        assert_eq!(bfs_path(&g, 1, |n| *n == 10), Some(vec![1, 3, 9, 10]));
+        assert_eq!(bfs_path(&sample(), 1, |n| *n == 5), None);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let g = sample();
+        let result = connected_components(&g, g.nodes().copied());
+        assert_eq!(result, vec![vec![1, 2, 3], vec![4, 5], vec![6]]);
+    }
+
+    #[test]
+    fn test_implicit_graph_walks() {
+        let g = ImplicitGraph::new(|n: &u32| [n * 2, n * 2 + 1]);
+        let result = walks(&g, 1, 2);
+        assert_eq!(result.len(), 1 + 2 + 4);
+        assert!(result.contains(&vec![1, 3, 6]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let g = ImplicitGraph::new(|n: &u32| if *n < 10 { vec![n + 1, n + 3] } else { vec![] });
+        let result = dijkstra(&g, 0, |a, b| if b - a == 3 { 2 } else { 1 });
+        assert_eq!(result[&9], 6);
+        assert_eq!(result[&10], 7);
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut g = AdjacencyList::new();
+        g.add_edge('a', 'c');
+        g.add_edge('b', 'c');
+        g.add_edge('c', 'd');
+        let result = topological_sort(&g, g.nodes().copied()).unwrap();
+        assert_eq!(result, vec!['a', 'b', 'c', 'd']);
+        g.add_edge('d', 'a');
+        assert_eq!(topological_sort(&g, g.nodes().copied()), None);
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut uf = UnionFind::new(5);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(3, 4));
+        assert!(uf.union(1, 4));
+        assert!(!uf.union(0, 3));
+        assert_eq!(uf.set_size(0), 4);
+        assert_eq!(uf.set_size(2), 1);
+    }
+}
@@ -0,0 +1,226 @@
+//! A dense, rectangular grid of cells addressed by [`Point`]s.
+
+use crate::geometry::{Point, Rect};
+use anyhow::anyhow;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(Point::new(x as i64, y as i64)));
+            }
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a grid from its cells in row-major order.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> anyhow::Result<Self> {
+        if cells.len() != width * height {
+            return Err(anyhow!(
+                "{} cells do not fill a {width}x{height} grid",
+                cells.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a grid with one row per line, converting each character with `f`.
+    ///
+    /// All lines must have the same length. Trailing empty lines are ignored.
+    pub fn parse(
+        input: &str,
+        mut f: impl FnMut(char) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Self> {
+        let lines: Vec<&str> = input.trim_end().lines().collect();
+        let width = lines.first().map(|l| l.chars().count()).unwrap_or_default();
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            if len != width {
+                return Err(anyhow!("line {} has {len} cells, expected {width}", y + 1));
+            }
+            for c in line.chars() {
+                cells.push(f(c)?);
+            }
+        }
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The rectangle of all cells, or `None` for a grid without cells.
+    pub fn bounds(&self) -> Option<Rect> {
+        (self.width > 0 && self.height > 0).then(|| {
+            Rect::new(
+                Point::new(0, 0),
+                Point::new(self.width as i64 - 1, self.height as i64 - 1),
+            )
+        })
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        (0..self.width as i64).contains(&p.x) && (0..self.height as i64).contains(&p.y)
+    }
+
+    fn index(&self, p: &Point) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn get(&self, p: &Point) -> Option<&T> {
+        self.index(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: &Point) -> Option<&mut T> {
+        self.index(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Stores `value` at `p`. Returns `false` if `p` is outside of the grid.
+    pub fn set(&mut self, p: &Point, value: T) -> bool {
+        match self.get_mut(p) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// All points of the grid row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// All cells together with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The points of `region` that lie inside the grid, with their cells.
+    /// Only the part of `region` overlapping the grid is visited.
+    pub fn region(&self, region: Rect) -> impl Iterator<Item = (Point, &T)> {
+        self.bounds()
+            .and_then(|bounds| bounds.intersection(&region))
+            .into_iter()
+            .flat_map(|overlap| overlap.points())
+            .filter_map(|p| self.get(&p).map(|cell| (p, cell)))
+    }
+
+    /// Orthogonal neighbors of `p` inside the grid.
+    pub fn neighbors4(&self, p: &Point) -> impl Iterator<Item = Point> {
+        p.neighbors4().filter(|n| self.contains(n))
+    }
+
+    /// Orthogonal and diagonal neighbors of `p` inside the grid.
+    pub fn neighbors8(&self, p: &Point) -> impl Iterator<Item = Point> {
+        p.neighbors8().filter(|n| self.contains(n))
+    }
+
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, cell)| pred(cell)).map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    /// Renders the grid as ASCII art, one line per row.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)) {
+            result.extend(row.iter().map(&mut f));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|c| *c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#..\n.#.\n..#\n#..\n";
+
+    #[test]
+    fn test_parse_and_render() {
+        let grid = Grid::parse(INPUT, |c| Ok(c == '#')).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 4);
+        assert_eq!(grid.get(&Point::new(1, 1)), Some(&true));
+        assert_eq!(grid.get(&Point::new(3, 1)), None);
+        assert_eq!(grid.render(|b| if *b { '#' } else { '.' }), INPUT);
+    }
+
+    #[test]
+    fn test_parse_ragged() {
+        let result = Grid::parse("##\n#", Ok);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_cells() {
+        let grid = Grid::from_cells(2, 2, vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(grid, Grid::from_fn(2, 2, |p| p.x + 2 * p.y + 1));
+        assert!(Grid::from_cells(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_neighbors_and_region() {
+        let grid = Grid::from_fn(3, 3, |p| p.x + p.y * 3);
+        assert_eq!(grid.neighbors4(&Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors8(&Point::new(1, 1)).count(), 8);
+        let region: Vec<i64> = grid
+            .region(Rect::new(Point::new(1, 1), Point::new(5, 5)))
+            .map(|(_, v)| *v)
+            .collect();
+        assert_eq!(region, vec![4, 5, 7, 8]);
+        // only the overlap is visited, so a huge region costs nothing extra
+        let huge = Rect::new(Point::new(i64::MIN, -1), Point::new(i64::MAX, 0));
+        assert_eq!(grid.region(huge).count(), 3);
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Point::new(0, 0), Point::new(2, 2)))
+        );
+        let empty = Grid::from_cells(0, 0, Vec::<i64>::new()).unwrap();
+        assert_eq!(empty.bounds(), None);
+        assert_eq!(empty.region(huge).count(), 0);
+    }
+}
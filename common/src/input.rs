@@ -0,0 +1,154 @@
+//! Loading of puzzle inputs, which may be kept encrypted at rest.
+//!
+//! An input `everybody_codes_e2025_q01_p1.txt` is read as plain text if it
+//! exists. Otherwise `everybody_codes_e2025_q01_p1.txt.enc` is decrypted with
+//! the passphrase from the `EC_INPUT_KEY` environment variable.
+//!
+//! Encrypted files consist of a magic header, a random nonce and the
+//! ChaCha20-Poly1305 ciphertext. The key is the SHA-256 hash of the passphrase.
+
+use anyhow::anyhow;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+pub const KEY_VARIABLE: &str = "EC_INPUT_KEY";
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+const MAGIC: &[u8; 4] = b"ECE1";
+const NONCE_SIZE: usize = 12;
+
+pub fn read_input(path: impl AsRef<Path>) -> anyhow::Result<String> {
+    read_input_with_key(path, key_from_env().as_ref())
+}
+
+pub fn read_input_with_key(
+    path: impl AsRef<Path>,
+    key: Option<&InputKey>,
+) -> anyhow::Result<String> {
+    let path = path.as_ref();
+    if path.is_file() {
+        let contents = std::fs::read_to_string(path)?;
+        return Ok(contents);
+    }
+    let encrypted_path = encrypted_path(path);
+    if !encrypted_path.is_file() {
+        return Err(anyhow!(
+            "Input {} not found (neither plain nor encrypted)",
+            path.display()
+        ));
+    }
+    let key = key.ok_or_else(|| {
+        anyhow!(
+            "Input {} is encrypted but {KEY_VARIABLE} is not set",
+            path.display()
+        )
+    })?;
+    let data = std::fs::read(&encrypted_path)?;
+    let plain = decrypt(&data, key)
+        .map_err(|e| anyhow!("Cannot decrypt {}: {e}", encrypted_path.display()))?;
+    Ok(plain)
+}
+
+/// Returns `true` for file names of personal puzzle inputs.
+pub fn is_input_file_name(name: &str) -> bool {
+    name.starts_with("everybody_codes_") && name.ends_with(".txt")
+}
+
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ENCRYPTED_EXTENSION);
+    PathBuf::from(name)
+}
+
+#[derive(Clone)]
+pub struct InputKey(Key);
+
+impl InputKey {
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        let hash = Sha256::digest(passphrase.as_bytes());
+        InputKey(hash)
+    }
+}
+
+pub fn key_from_env() -> Option<InputKey> {
+    std::env::var(KEY_VARIABLE)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(|s| InputKey::from_passphrase(&s))
+}
+
+pub fn encrypt(plain: &str, key: &InputKey) -> anyhow::Result<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(&key.0);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain.as_bytes())
+        .map_err(|e| anyhow!("encryption failed: {e}"))?;
+    let mut result = Vec::with_capacity(MAGIC.len() + NONCE_SIZE + ciphertext.len());
+    result.extend_from_slice(MAGIC);
+    result.extend_from_slice(&nonce);
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+pub fn decrypt(data: &[u8], key: &InputKey) -> anyhow::Result<String> {
+    let data = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| anyhow!("not an encrypted input file"))?;
+    if data.len() < NONCE_SIZE {
+        return Err(anyhow!("encrypted input file is truncated"));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    let cipher = ChaCha20Poly1305::new(&key.0);
+    let plain = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("wrong key or corrupted file"))?;
+    let result = String::from_utf8(plain)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("common-input-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key = InputKey::from_passphrase("secret");
+        let data = encrypt("Vyrdax,Drakzyph", &key).unwrap();
+        assert_eq!(decrypt(&data, &key).unwrap(), "Vyrdax,Drakzyph");
+    }
+
+    #[test]
+    fn test_wrong_key() {
+        let key = InputKey::from_passphrase("secret");
+        let data = encrypt("Vyrdax,Drakzyph", &key).unwrap();
+        let other = InputKey::from_passphrase("other");
+        assert!(decrypt(&data, &other).is_err());
+    }
+
+    #[test]
+    fn test_read_encrypted_input() {
+        let key = InputKey::from_passphrase("secret");
+        let path = temp_file("q01_p1.txt");
+        let encrypted = encrypted_path(&path);
+        std::fs::write(&encrypted, encrypt("R3,L2", &key).unwrap()).unwrap();
+        let result = read_input_with_key(&path, Some(&key));
+        let missing_key = read_input_with_key(&path, None);
+        std::fs::remove_file(&encrypted).unwrap();
+        assert_eq!(result.unwrap(), "R3,L2");
+        assert!(missing_key.is_err());
+    }
+
+    #[test]
+    fn test_is_input_file_name() {
+        assert!(is_input_file_name("everybody_codes_e2025_q01_p1.txt"));
+        assert!(!is_input_file_name("everybody_codes_e2025_q01_p1.txt.enc"));
+        assert!(!is_input_file_name("answers.txt"));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod explain;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
@@ -0,0 +1,231 @@
+use crate::Instruction;
+use crate::rules::{MovementRule, WheelError, simulate_observed};
+use common::geometry::circle_position;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// All names on one line.
+    Row,
+    /// Names spread on a circle, starting at the top and going clockwise.
+    Wheel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// The selected name is shown as `[Name]` and moved names as `*Name*`.
+    Plain,
+    /// The selected name is shown in reverse video and moved names in yellow.
+    Ansi,
+}
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Renders one frame for the start and one for each instruction, using the
+/// same simulation as the parts.
+///
+/// Names that changed position since the previous frame are marked, which
+/// for `SwapWithFirst` is the swapped pair.
+pub fn frames(
+    rule: &(impl MovementRule + ?Sized),
+    names: &[String],
+    instructions: &[Instruction],
+    layout: Layout,
+    style: Style,
+) -> Result<Vec<String>, WheelError> {
+    let mut frames = Vec::with_capacity(instructions.len() + 1);
+    let mut previous: Option<Vec<String>> = None;
+    simulate_observed(rule, names, instructions, |names, cursor| {
+        let step = frames.len();
+        let moved: Vec<bool> = match &previous {
+            Some(previous) => previous.iter().zip(names).map(|(a, b)| a != b).collect(),
+            None => vec![false; names.len()],
+        };
+        let selected = rule.selected(cursor);
+        let labels: Vec<Label> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Label::new(name, i == selected, moved[i], style))
+            .collect();
+        let title = match step {
+            0 => "start".to_string(),
+            _ => format!(
+                "step {step}/{}: {}",
+                instructions.len(),
+                instructions[step - 1]
+            ),
+        };
+        let body = match layout {
+            Layout::Row => render_row(&labels),
+            Layout::Wheel => render_wheel(&labels),
+        };
+        frames.push(format!("{title}\n{body}"));
+        previous = Some(names.to_vec());
+    })?;
+    Ok(frames)
+}
+
+/// Plays `frames` in the terminal, clearing the screen before each one.
+pub fn play(frames: &[String], delay: Duration, mut out: impl Write) -> io::Result<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        write!(out, "{CLEAR_SCREEN}{frame}")?;
+        out.flush()?;
+        if i + 1 < frames.len() {
+            thread::sleep(delay);
+        }
+    }
+    Ok(())
+}
+
+/// A decorated name and the number of columns it takes on screen.
+struct Label {
+    text: String,
+    width: usize,
+    name_width: usize,
+}
+
+impl Label {
+    fn new(name: &str, selected: bool, moved: bool, style: Style) -> Self {
+        match style {
+            Style::Plain => {
+                let mut text = name.to_string();
+                if moved {
+                    text = format!("*{text}*");
+                }
+                if selected {
+                    text = format!("[{text}]");
+                }
+                Label {
+                    width: text.chars().count(),
+                    text,
+                    name_width: name.chars().count(),
+                }
+            }
+            Style::Ansi => {
+                let codes: Vec<&str> = [(selected, "7"), (moved, "33")]
+                    .iter()
+                    .filter(|(on, _)| *on)
+                    .map(|(_, code)| *code)
+                    .collect();
+                let text = if codes.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("\x1b[{}m{name}\x1b[0m", codes.join(";"))
+                };
+                Label {
+                    text,
+                    width: name.chars().count(),
+                    name_width: name.chars().count(),
+                }
+            }
+        }
+    }
+}
+
+fn render_row(labels: &[Label]) -> String {
+    let texts: Vec<&str> = labels.iter().map(|label| label.text.as_str()).collect();
+    format!("{}\n", texts.join(" "))
+}
+
+fn render_wheel(labels: &[Label]) -> String {
+    let count = labels.len();
+    // sized by the plain names, so markers do not move the whole wheel
+    let max_width = labels.iter().map(|l| l.name_width).max().unwrap_or(0) + 4;
+    // characters are about twice as high as wide
+    let radius_y = (count as f64 / 2.0).max(2.0);
+    let radius_x = 2.0 * radius_y + max_width as f64;
+    let center_x = radius_x + max_width as f64;
+    let mut rows: Vec<Vec<(usize, &Label)>> = vec![Vec::new(); 2 * radius_y as usize + 1];
+    for (i, label) in labels.iter().enumerate() {
+        let (x, y) = circle_position(i, count, 1.0);
+        let row = (radius_y + y * radius_y).round() as usize;
+        let column = (center_x + x * radius_x - label.width as f64 / 2.0).round() as usize;
+        rows[row].push((column, label));
+    }
+    let mut result = String::new();
+    for row in rows.iter_mut() {
+        row.sort_by_key(|(column, _)| *column);
+        let mut used = 0;
+        for (column, label) in row.iter() {
+            // labels that would overlap are pushed to the right
+            let column = (*column).max(if used == 0 { 0 } else { used + 1 });
+            result.push_str(&" ".repeat(column - used));
+            result.push_str(&label.text);
+            used = column + label.width;
+        }
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input_data;
+    use crate::rules::{Clamp, SwapWithFirst};
+
+    const INPUT: &str = r#"Vyrdax,Drakzyph,Fyrryn,Elarzris
+
+R3,L2,R3,L1"#;
+
+    fn render(rule: &impl MovementRule, layout: Layout, style: Style) -> Vec<String> {
+        let mut inp = INPUT;
+        let input_data = parse_input_data(&mut inp).unwrap();
+        frames(
+            rule,
+            &input_data.names,
+            &input_data.instructions,
+            layout,
+            style,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_row_frames() {
+        let frames = render(&Clamp, Layout::Row, Style::Plain);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0], "start\n[Vyrdax] Drakzyph Fyrryn Elarzris\n");
+        assert_eq!(
+            frames[4],
+            "step 4/4: L1\nVyrdax Drakzyph [Fyrryn] Elarzris\n"
+        );
+    }
+
+    #[test]
+    fn test_swapped_pair_is_marked() {
+        let frames = render(&SwapWithFirst, Layout::Row, Style::Plain);
+        assert_eq!(
+            frames[1],
+            "step 1/4: R3\n[*Elarzris*] Drakzyph Fyrryn *Vyrdax*\n"
+        );
+        let frames = render(&SwapWithFirst, Layout::Row, Style::Ansi);
+        assert!(frames[1].contains("\x1b[7;33mElarzris\x1b[0m"));
+        assert!(frames[1].contains("\x1b[33mVyrdax\x1b[0m"));
+    }
+
+    #[test]
+    fn test_wheel_layout() {
+        let frames = render(&Clamp, Layout::Wheel, Style::Plain);
+        let lines: Vec<&str> = frames[1].lines().collect();
+        assert_eq!(lines[0], "step 1/4: R3");
+        // top, then left and right on the middle row, then bottom
+        assert_eq!(lines[1].trim(), "Vyrdax");
+        assert!(lines[3].trim().starts_with("[Elarzris]"));
+        assert!(lines[3].trim().ends_with("Drakzyph"));
+        assert_eq!(lines[5].trim(), "Fyrryn");
+    }
+
+    #[test]
+    fn test_play_clears_the_screen() {
+        let frames = vec!["a\n".to_string(), "b\n".to_string()];
+        let mut out = Vec::new();
+        play(&frames, Duration::ZERO, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("{CLEAR_SCREEN}a\n{CLEAR_SCREEN}b\n")
+        );
+    }
+}
@@ -1,27 +1,204 @@
+mod animate;
+mod permutation;
+mod program;
+mod rules;
+mod search;
+mod stream;
+
+use animate::{Layout, Style};
 use anyhow::anyhow;
-use std::path::Path;
+use common::explain::{EXPLAIN_FLAG, Explain};
+use common::input::read_input;
+use permutation::Permutation;
+use program::{Program, parse_program};
+use rules::{Clamp, Outcome, SwapWithFirst, WheelError, Wrap, rule_by_name, simulate};
+use search::shortest_instructions;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::time::Duration;
+use stream::{simulate_stream, write_generated};
 use winnow::{
     ModalResult, Parser,
-    ascii::{alpha1, digit1, line_ending},
-    combinator::{alt, separated},
+    ascii::{alpha1, digit1, line_ending, multispace0},
+    combinator::{alt, eof, separated, terminated},
 };
 
+const USAGE: &str = "Usage:
+  quest1 [--explain]                       solve all parts
+  quest1 simulate <rule> <input>           run clamp, wrap, swap or bounce
+  quest1 run <rule> <input>                run the extended instruction language
+  quest1 stream <rule> <file>              simulate while reading, in constant memory
+  quest1 generate <names> <count> [seed]   print a random input with count instructions
+  quest1 animate <rule> <input> [--wheel] [--play <delay-ms>]
+                                           draw every step, or play them in the terminal
+  quest1 repeat <k> <input> [position]     apply the part 3 swaps k times
+  quest1 search <rule> <input> <name> <max-steps>
+                                           find the shortest instructions selecting name";
+
 fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != EXPLAIN_FLAG)
+        .collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] => solve(),
+        ["simulate", rule, file] => run_simulation(rule, file),
+        ["run", rule, file] => run_program(rule, file),
+        ["stream", rule, file] => run_stream(rule, file),
+        ["generate", names, count] => run_generate(names, count, "1"),
+        ["generate", names, count, seed] => run_generate(names, count, seed),
+        ["animate", rule, file, options @ ..] => run_animation(rule, file, options),
+        ["repeat", k, file] => run_repeated_swaps(k, file, None),
+        ["repeat", k, file, position] => run_repeated_swaps(k, file, Some(position)),
+        ["search", rule, file, target, max_steps] => run_search(rule, file, target, max_steps),
+        _ => Err(anyhow!("{USAGE}")),
+    }
+}
+
+fn solve() -> anyhow::Result<()> {
     let input = read_input("everybody_codes_e2025_q01_p1.txt")?;
-    let result = part1(&input)?;
+    let mut explain = Explain::from_args();
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
     let input = read_input("everybody_codes_e2025_q01_p2.txt")?;
-    let result = part2(&input)?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
     let input = read_input("everybody_codes_e2025_q01_p3.txt")?;
-    let result = part3(&input)?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
+    Ok(())
+}
+
+/// Runs the instructions of `file` under the named movement rule and prints
+/// the cursor history and the final arrangement.
+fn run_simulation(rule: &str, file: &str) -> anyhow::Result<()> {
+    let rule = rule_by_name(rule).ok_or_else(|| anyhow!("Unknown rule: {rule}"))?;
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
+    let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    let outcome = simulate(rule.as_ref(), &input_data.names, &input_data.instructions)?;
+    println!("history: {:?}", outcome.history);
+    println!("names: {}", outcome.names.join(","));
+    println!("{}", outcome.name);
+    Ok(())
+}
+
+/// Runs the names of `file` through a program written in the extended
+/// instruction language (`J5`, `S2:7`, `V2-6`, `T3`, `(R3,L2)x10`, ...).
+fn run_program(rule: &str, file: &str) -> anyhow::Result<()> {
+    let rule = rule_by_name(rule).ok_or_else(|| anyhow!("Unknown rule: {rule}"))?;
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
+    let (names, ops) = (parse_names, parse_program)
+        .parse_next(&mut inp)
+        .map_err(|e| anyhow!("{e}"))?;
+    let outcome = program::run(rule.as_ref(), &names, &ops)?;
+    println!("program: {}", Program(&ops));
+    println!("history: {:?}", outcome.history);
+    println!("names: {}", outcome.names.join(","));
+    println!("{}", outcome.name);
+    Ok(())
+}
+
+/// Simulates the plain input `file` while reading it, so inputs far larger
+/// than memory can be processed.
+fn run_stream(rule: &str, file: &str) -> anyhow::Result<()> {
+    let rule = rule_by_name(rule).ok_or_else(|| anyhow!("Unknown rule: {rule}"))?;
+    let reader = BufReader::with_capacity(1 << 16, File::open(file)?);
+    let outcome = simulate_stream(rule.as_ref(), reader)?;
+    println!("instructions: {}", outcome.instructions);
+    println!("cursor: {}", outcome.cursor);
+    println!("names: {}", outcome.names.join(","));
+    println!("{}", outcome.name);
+    Ok(())
+}
+
+/// Prints a generated input for `run_stream`.
+fn run_generate(names: &str, count: &str, seed: &str) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(io::stdout().lock());
+    write_generated(&mut writer, names.parse()?, count.parse()?, seed.parse()?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Draws each step of the simulation of `file`, as text frames or, with
+/// `--play`, as an animation in the terminal.
+fn run_animation(rule: &str, file: &str, options: &[&str]) -> anyhow::Result<()> {
+    let rule = rule_by_name(rule).ok_or_else(|| anyhow!("Unknown rule: {rule}"))?;
+    let mut layout = Layout::Row;
+    let mut delay = None;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match *option {
+            "--wheel" => layout = Layout::Wheel,
+            "--play" => {
+                let ms = options.next().ok_or_else(|| anyhow!("{USAGE}"))?;
+                delay = Some(Duration::from_millis(ms.parse()?));
+            }
+            _ => return Err(anyhow!("{USAGE}")),
+        }
+    }
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
+    let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    let style = if delay.is_some() {
+        Style::Ansi
+    } else {
+        Style::Plain
+    };
+    let frames = animate::frames(
+        rule.as_ref(),
+        &input_data.names,
+        &input_data.instructions,
+        layout,
+        style,
+    )?;
+    match delay {
+        Some(delay) => animate::play(&frames, delay, io::stdout().lock())?,
+        None => println!("{}", frames.join("\n")),
+    }
+    Ok(())
+}
+
+/// Applies the part 3 swaps of `file` `k` times and prints the resulting
+/// arrangement, or only the name at `position`.
+fn run_repeated_swaps(k: &str, file: &str, position: Option<&str>) -> anyhow::Result<()> {
+    let k: u64 = k.parse()?;
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
+    let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    let perm = Permutation::from_swaps(input_data.names.len(), &input_data.instructions)?.pow(k);
+    match position {
+        Some(position) => {
+            let position: usize = position.parse()?;
+            let idx = perm
+                .source(position)
+                .ok_or_else(|| anyhow!("Position {position} is out of range"))?;
+            println!("{}", input_data.names[idx]);
+        }
+        None => println!("{}", perm.apply(&input_data.names).join(",")),
+    }
     Ok(())
 }
 
-fn read_input(filename: impl AsRef<Path>) -> anyhow::Result<String> {
-    let contents = std::fs::read_to_string(filename)?;
-    Ok(contents)
+/// Prints the shortest instructions that select `target` from the names of
+/// `file` under the named rule.
+fn run_search(rule: &str, file: &str, target: &str, max_steps: &str) -> anyhow::Result<()> {
+    let rule = rule_by_name(rule).ok_or_else(|| anyhow!("Unknown rule: {rule}"))?;
+    let max_steps: i64 = max_steps.parse()?;
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
+    let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    let instructions = shortest_instructions(rule.as_ref(), &input_data.names, target, max_steps)
+        .ok_or_else(|| anyhow!("{target} cannot be selected"))?;
+    let instructions: Vec<String> = instructions.iter().map(|i| i.to_string()).collect();
+    println!("{}", instructions.join(","));
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -30,90 +207,90 @@ struct InputData {
     instructions: Vec<Instruction>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     Left,
     Right,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Instruction {
     direction: Direction,
-    steps: i32,
+    steps: i64,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        };
+        write!(f, "{direction}{}", self.steps)
+    }
 }
 
-fn part1(input: &str) -> anyhow::Result<String> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<String> {
     let mut inp = input;
     let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let size = input_data.names.len() as i32;
-    let idx =
-        input_data
-            .instructions
-            .iter()
-            .fold(0, |idx, instruction| match instruction.direction {
-                Direction::Left => clamp(idx - instruction.steps, 0, size - 1),
-                Direction::Right => clamp(idx + instruction.steps, 0, size - 1),
-            }) as usize;
-    Ok(input_data.names[idx].clone())
-}
-
-fn part2(input: &str) -> anyhow::Result<String> {
+    let outcome = simulate(&Clamp, &input_data.names, &input_data.instructions)?;
+    explain_indices(explain, &input_data, &outcome);
+    Ok(outcome.name)
+}
+
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<String> {
     let mut inp = input;
     let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let size = input_data.names.len() as i32;
-    let idx = input_data
-        .instructions
-        .iter()
-        .fold(0i32, |idx, instruction| match instruction.direction {
-            Direction::Left => (idx - instruction.steps).rem_euclid(size),
-            Direction::Right => (idx + instruction.steps).rem_euclid(size),
-        }) as usize;
-    Ok(input_data.names[idx].clone())
-}
-
-fn part3(input: &str) -> anyhow::Result<String> {
+    let outcome = simulate(&Wrap, &input_data.names, &input_data.instructions)?;
+    explain_indices(explain, &input_data, &outcome);
+    Ok(outcome.name)
+}
+
+fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<String> {
     let mut inp = input;
-    let mut input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let size = input_data.names.len() as i32;
-    for instruction in input_data.instructions.iter() {
-        let idx = match instruction.direction {
-            Direction::Left => (-instruction.steps).rem_euclid(size),
-            Direction::Right => (instruction.steps).rem_euclid(size),
-        };
-        input_data.names.swap(0, idx as usize);
+    let input_data = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    let outcome = simulate(&SwapWithFirst, &input_data.names, &input_data.instructions)?;
+    for (instruction, idx) in input_data.instructions.iter().zip(&outcome.history[1..]) {
+        explain.step(|| format!("{instruction}: swap 0 <-> {idx}"));
     }
-    Ok(input_data.names.first().cloned().unwrap())
+    Ok(outcome.name)
 }
 
-fn clamp(n: i32, min: i32, max: i32) -> i32 {
-    if n < min {
-        return min;
+fn explain_indices(explain: &mut Explain, input_data: &InputData, outcome: &Outcome) {
+    for (instruction, idx) in input_data.instructions.iter().zip(&outcome.history[1..]) {
+        explain.step(|| format!("{instruction}: index {idx} ({})", input_data.names[*idx]));
     }
-    if n > max {
-        return max;
-    }
-    n
 }
+
 fn parse_input_data(input: &mut &str) -> ModalResult<InputData> {
-    let names: Vec<String> =
-        separated(1.., alpha1.map(|s: &str| s.to_string()), ",").parse_next(input)?;
-    (line_ending, line_ending).parse_next(input)?;
+    let names = parse_names(input)?;
     let instructions: Vec<Instruction> =
-        separated(1.., parse_instruction, ",").parse_next(input)?;
+        terminated(separated(1.., parse_instruction, ","), (multispace0, eof)).parse_next(input)?;
     Ok(InputData {
         names,
         instructions,
     })
 }
 
+/// Parses the names and the blank line separating them from the instructions.
+fn parse_names(input: &mut &str) -> ModalResult<Vec<String>> {
+    let names = separated(1.., alpha1.map(|s: &str| s.to_string()), ",").parse_next(input)?;
+    (line_ending, line_ending).parse_next(input)?;
+    Ok(names)
+}
+
 fn parse_instruction(input: &mut &str) -> ModalResult<Instruction> {
     (parse_direction, parse_int)
         .map(|(direction, steps)| Instruction { direction, steps })
         .parse_next(input)
 }
 
-fn parse_int(input: &mut &str) -> ModalResult<i32> {
-    digit1.parse_to::<i32>().parse_next(input)
+fn parse_int(input: &mut &str) -> ModalResult<i64> {
+    digit1
+        .try_map(|s: &str| {
+            s.parse::<i64>()
+                .map_err(|_| WheelError::OutOfRange(s.to_string()))
+        })
+        .parse_next(input)
 }
 
 fn parse_direction(input: &mut &str) -> ModalResult<Direction> {
@@ -130,16 +307,51 @@ R3,L2,R3,L1"#;
 
     #[test]
     fn part1_works() {
-        assert_eq!(part1(INPUT).expect("no error"), "Fyrryn");
+        assert_eq!(
+            part1(INPUT, &mut Explain::disabled()).expect("no error"),
+            "Fyrryn"
+        );
     }
 
     #[test]
     fn part2_works() {
-        assert_eq!(part2(INPUT).expect("no error"), "Elarzris");
+        assert_eq!(
+            part2(INPUT, &mut Explain::disabled()).expect("no error"),
+            "Elarzris"
+        );
+    }
+
+    #[test]
+    fn part1_explained() {
+        let mut explain = Explain::new(true);
+        part1(INPUT, &mut explain).expect("no error");
+        assert_eq!(
+            explain.lines(),
+            [
+                "R3: index 3 (Elarzris)",
+                "L2: index 1 (Drakzyph)",
+                "R3: index 3 (Elarzris)",
+                "L1: index 2 (Fyrryn)"
+            ]
+        );
+    }
+
+    #[test]
+    fn huge_and_overflowing_steps() {
+        let input = "Vyrdax,Drakzyph\n\nR9223372036854775807";
+        assert_eq!(
+            part2(input, &mut Explain::disabled()).expect("no error"),
+            "Drakzyph"
+        );
+        let input = "Vyrdax,Drakzyph\n\nR99999999999999999999";
+        assert!(part2(input, &mut Explain::disabled()).is_err());
     }
 
     #[test]
     fn part3_works() {
-        assert_eq!(part3(INPUT).expect("no error"), "Fyrryn");
+        assert_eq!(
+            part3(INPUT, &mut Explain::disabled()).expect("no error"),
+            "Fyrryn"
+        );
     }
 }
@@ -0,0 +1,91 @@
+use crate::Instruction;
+use crate::rules::{SwapWithFirst, WheelError};
+
+/// A rearrangement of a list: position `i` receives the element previously
+/// at `self.0[i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permutation(Vec<usize>);
+
+impl Permutation {
+    pub fn identity(size: usize) -> Self {
+        Permutation((0..size).collect())
+    }
+
+    /// Compiles one pass of the part 3 swaps over `size` names.
+    pub fn from_swaps(size: usize, instructions: &[Instruction]) -> Result<Self, WheelError> {
+        let mut result = Self::identity(size);
+        for instruction in instructions.iter() {
+            let target = SwapWithFirst.target(size, instruction)?;
+            result.0.swap(0, target);
+        }
+        Ok(result)
+    }
+
+    /// Applies `other` after `self`.
+    pub fn then(&self, other: &Permutation) -> Permutation {
+        Permutation(other.0.iter().map(|&i| self.0[i]).collect())
+    }
+
+    /// Applies the permutation `k` times, using repeated squaring.
+    pub fn pow(&self, mut k: u64) -> Permutation {
+        let mut result = Self::identity(self.0.len());
+        let mut base = self.clone();
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result.then(&base);
+            }
+            base = base.then(&base);
+            k >>= 1;
+        }
+        result
+    }
+
+    /// Index into the original list of the element that ends up at `position`.
+    pub fn source(&self, position: usize) -> Option<usize> {
+        self.0.get(position).copied()
+    }
+
+    pub fn apply<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        self.0.iter().map(|&i| items[i].clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input_data;
+    use crate::rules::simulate;
+
+    const INPUT: &str = r#"Vyrdax,Drakzyph,Fyrryn,Elarzris,Xaryt,Oronris
+
+R3,L2,R8,L1,R4,L5,R2"#;
+
+    #[test]
+    fn test_pow_matches_repeated_simulation() {
+        let mut inp = INPUT;
+        let input_data = parse_input_data(&mut inp).unwrap();
+        let perm =
+            Permutation::from_swaps(input_data.names.len(), &input_data.instructions).unwrap();
+        let mut names = input_data.names.clone();
+        for k in 1..=12 {
+            names = simulate(&SwapWithFirst, &names, &input_data.instructions)
+                .unwrap()
+                .names;
+            assert_eq!(perm.pow(k).apply(&input_data.names), names, "k={k}");
+        }
+    }
+
+    #[test]
+    fn test_pow_large_exponent() {
+        let mut inp = INPUT;
+        let input_data = parse_input_data(&mut inp).unwrap();
+        let perm =
+            Permutation::from_swaps(input_data.names.len(), &input_data.instructions).unwrap();
+        let order = (1..=720)
+            .find(|&k| perm.pow(k) == Permutation::identity(6))
+            .unwrap();
+        let k = 1_000_000_000_000;
+        assert_eq!(perm.pow(k), perm.pow(k % order));
+        assert!(perm.pow(k).source(0).is_some());
+    }
+}
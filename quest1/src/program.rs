@@ -0,0 +1,258 @@
+use crate::rules::{MovementRule, Outcome, WheelError, list_size};
+use crate::{Instruction, parse_instruction, parse_int};
+use std::fmt;
+use winnow::{
+    ModalResult, Parser,
+    combinator::{alt, delimited, preceded, separated, separated_pair},
+};
+
+/// Most steps a program may run, repeats included. Every step adds to the
+/// history, so this also bounds its memory.
+pub const MAX_STEPS: u64 = 10_000_000;
+
+/// One step of the extended instruction language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    /// `R3` / `L2`: the plain instructions of the puzzle.
+    Move(Instruction),
+    /// `J5`: moves the cursor to an absolute index.
+    Jump(i64),
+    /// `S2:7`: swaps the names at two indices.
+    Swap(i64, i64),
+    /// `V2-6`: reverses the names between two indices, both included.
+    Reverse(i64, i64),
+    /// `T3`: rotates the whole list to the right.
+    Rotate(i64),
+    /// `(R3,L2)x10`: runs a group of steps several times.
+    Repeat(Vec<Op>, i64),
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Move(instruction) => write!(f, "{instruction}"),
+            Op::Jump(index) => write!(f, "J{index}"),
+            Op::Swap(a, b) => write!(f, "S{a}:{b}"),
+            Op::Reverse(a, b) => write!(f, "V{a}-{b}"),
+            Op::Rotate(steps) => write!(f, "T{steps}"),
+            Op::Repeat(ops, count) => write!(f, "({})x{count}", Program(ops)),
+        }
+    }
+}
+
+/// Displays a list of steps the way they are written in the input.
+pub struct Program<'a>(pub &'a [Op]);
+
+impl fmt::Display for Program<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, op) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{op}")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_program(input: &mut &str) -> ModalResult<Vec<Op>> {
+    separated(1.., parse_op, ",").parse_next(input)
+}
+
+fn parse_op(input: &mut &str) -> ModalResult<Op> {
+    alt((
+        (delimited('(', parse_program, ")x"), parse_int).map(|(ops, count)| Op::Repeat(ops, count)),
+        preceded('J', parse_int).map(Op::Jump),
+        preceded('S', separated_pair(parse_int, ':', parse_int)).map(|(a, b)| Op::Swap(a, b)),
+        preceded('V', separated_pair(parse_int, '-', parse_int)).map(|(a, b)| Op::Reverse(a, b)),
+        preceded('T', parse_int).map(Op::Rotate),
+        parse_instruction.map(Op::Move),
+    ))
+    .parse_next(input)
+}
+
+/// Runs `ops` over `names` under `rule`, starting at index 0.
+///
+/// Indices are resolved by the rule, so they stop at the ends under `Clamp`
+/// and wrap around otherwise. Rotations always wrap, as their amount is not an
+/// index. Swaps, reversals and rotations rearrange the names but leave the
+/// cursor at its position. Programs running more than [`MAX_STEPS`] steps
+/// fail.
+pub fn run(
+    rule: &(impl MovementRule + ?Sized),
+    names: &[String],
+    ops: &[Op],
+) -> Result<Outcome, WheelError> {
+    if names.is_empty() {
+        return Err(WheelError::EmptyNames);
+    }
+    let mut interpreter = Interpreter {
+        rule,
+        names: names.to_vec(),
+        cursor: 0,
+        history: vec![0],
+        steps: 0,
+    };
+    interpreter.execute(ops)?;
+    let name = interpreter.names[rule.selected(interpreter.cursor)].clone();
+    Ok(Outcome {
+        name,
+        names: interpreter.names,
+        history: interpreter.history,
+    })
+}
+
+struct Interpreter<'a, R: ?Sized> {
+    rule: &'a R,
+    names: Vec<String>,
+    cursor: usize,
+    history: Vec<usize>,
+    /// Steps run so far.
+    steps: u64,
+}
+
+impl<R: MovementRule + ?Sized> Interpreter<'_, R> {
+    fn execute(&mut self, ops: &[Op]) -> Result<(), WheelError> {
+        for op in ops.iter() {
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                return Err(WheelError::TooManySteps(MAX_STEPS));
+            }
+            match op {
+                Op::Repeat(group, count) => {
+                    for _ in 0..*count {
+                        self.execute(group)?;
+                    }
+                    continue;
+                }
+                Op::Move(instruction) => {
+                    self.cursor = self.rule.apply(&mut self.names, self.cursor, instruction)?;
+                }
+                Op::Jump(index) => {
+                    self.cursor = self.rule.jump(&mut self.names, *index)?;
+                }
+                Op::Swap(a, b) => {
+                    let a = self.rule.index(self.names.len(), *a)?;
+                    let b = self.rule.index(self.names.len(), *b)?;
+                    self.names.swap(a, b);
+                }
+                Op::Reverse(a, b) => {
+                    let a = self.rule.index(self.names.len(), *a)?;
+                    let b = self.rule.index(self.names.len(), *b)?;
+                    self.names[a.min(b)..=a.max(b)].reverse();
+                }
+                Op::Rotate(steps) => {
+                    let len = list_size(self.names.len())?;
+                    self.names.rotate_right(steps.rem_euclid(len) as usize);
+                }
+            }
+            self.history.push(self.cursor);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Bounce, Clamp, SwapWithFirst, Wrap, simulate};
+
+    fn names() -> Vec<String> {
+        [
+            "Vyrdax", "Drakzyph", "Fyrryn", "Elarzris", "Xaryt", "Oronris",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn parse(program: &str) -> Vec<Op> {
+        parse_program.parse(program).expect("valid program")
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let source = "J5,S2:7,V2-6,T3,(R3,L2)x10,((J1,T1)x2,L4)x3";
+        let ops = parse(source);
+        assert_eq!(ops.len(), 6);
+        assert_eq!(ops[1], Op::Swap(2, 7));
+        assert_eq!(Program(&ops).to_string(), source);
+        assert!(parse_program.parse("(R3,L2)").is_err());
+        assert!(parse_program.parse("X3").is_err());
+    }
+
+    #[test]
+    fn test_rearranging_steps() {
+        let outcome = run(&Wrap, &names(), &parse("S1:4,V0-2,T2,J3")).unwrap();
+        assert_eq!(
+            outcome.names,
+            [
+                "Drakzyph", "Oronris", "Fyrryn", "Xaryt", "Vyrdax", "Elarzris"
+            ]
+        );
+        assert_eq!(outcome.history, [0, 0, 0, 0, 3]);
+        assert_eq!(outcome.name, "Xaryt");
+    }
+
+    #[test]
+    fn test_indices_follow_the_rule() {
+        assert_eq!(
+            run(&Clamp, &names(), &parse("J99")).unwrap().name,
+            "Oronris"
+        );
+        assert_eq!(run(&Wrap, &names(), &parse("J8")).unwrap().name, "Fyrryn");
+        // under the part 3 rule a jump swaps the target to the front
+        let outcome = run(&SwapWithFirst, &names(), &parse("J8")).unwrap();
+        assert_eq!(outcome.name, "Fyrryn");
+        assert_eq!(outcome.names[2], "Vyrdax");
+        // under bounce an index past either end reflects back like a move
+        assert_eq!(
+            run(&Bounce, &names(), &parse("J7")).unwrap().name,
+            "Elarzris"
+        );
+        assert_eq!(
+            run(&Bounce, &names(), &parse("J12")).unwrap().name,
+            "Fyrryn"
+        );
+        let outcome = run(&Bounce, &names(), &parse("S0:8")).unwrap();
+        assert_eq!(outcome.names[..3], ["Fyrryn", "Drakzyph", "Vyrdax"]);
+    }
+
+    #[test]
+    fn test_plain_instructions_match_simulation() {
+        let mut inp = "R3,L2,R8,L1,R4,L5,R2";
+        let instructions: Vec<Instruction> = separated(1.., parse_instruction, ",")
+            .parse_next(&mut inp)
+            .unwrap();
+        let ops = parse("R3,L2,R8,L1,R4,L5,R2");
+        for rule in ["clamp", "wrap", "swap"] {
+            let rule = crate::rules::rule_by_name(rule).unwrap();
+            let expected = simulate(rule.as_ref(), &names(), &instructions).unwrap();
+            let outcome = run(rule.as_ref(), &names(), &ops).unwrap();
+            assert_eq!(outcome.names, expected.names);
+            assert_eq!(outcome.history, expected.history);
+        }
+    }
+
+    #[test]
+    fn test_rotation_wraps_under_every_rule() {
+        for rule in ["clamp", "wrap", "swap"] {
+            let rule = crate::rules::rule_by_name(rule).unwrap();
+            let outcome = run(rule.as_ref(), &names(), &parse("T7")).unwrap();
+            assert_eq!(outcome.names[1], "Vyrdax");
+        }
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let error = run(&Wrap, &names(), &parse("((R1)x999999,L1)x999999")).unwrap_err();
+        assert_eq!(error, WheelError::TooManySteps(MAX_STEPS));
+    }
+
+    #[test]
+    fn test_repeat_matches_expansion() {
+        let repeated = run(&Wrap, &names(), &parse("(R3,S0:1)x3,L1")).unwrap();
+        let expanded = run(&Wrap, &names(), &parse("R3,S0:1,R3,S0:1,R3,S0:1,L1")).unwrap();
+        assert_eq!(repeated.names, expanded.names);
+        assert_eq!(repeated.history, expanded.history);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::{Direction, Instruction};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WheelError {
+    /// There are no names to select from.
+    EmptyNames,
+    /// A value does not fit the range the wheel can handle.
+    OutOfRange(String),
+    /// A program would run more than this many steps.
+    TooManySteps(u64),
+}
+
+impl fmt::Display for WheelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WheelError::EmptyNames => write!(f, "the list of names is empty"),
+            WheelError::OutOfRange(value) => write!(f, "value out of range: {value}"),
+            WheelError::TooManySteps(limit) => {
+                write!(f, "the program runs more than {limit} steps")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WheelError {}
+
+/// Decides how an instruction moves the cursor over the list of names.
+pub trait MovementRule {
+    /// Applies `instruction` to the cursor at `idx`, possibly rearranging
+    /// `names`, and returns the new cursor position.
+    fn apply(
+        &self,
+        names: &mut [String],
+        idx: usize,
+        instruction: &Instruction,
+    ) -> Result<usize, WheelError>;
+
+    /// Position of the selected name for the final cursor position.
+    fn selected(&self, cursor: usize) -> usize {
+        cursor
+    }
+
+    /// Resolves an absolute `index` into a list of `size` names.
+    fn index(&self, size: usize, index: i64) -> Result<usize, WheelError> {
+        let size = list_size(size)?;
+        Ok(index.rem_euclid(size) as usize)
+    }
+
+    /// Moves the cursor straight to `index` and returns the new position.
+    fn jump(&self, names: &mut [String], index: i64) -> Result<usize, WheelError> {
+        self.index(names.len(), index)
+    }
+}
+
+/// Moves the cursor and stops at both ends of the list (part 1).
+pub struct Clamp;
+
+/// Moves the cursor around the list as if it was a wheel (part 2).
+pub struct Wrap;
+
+/// Swaps the first name with the one the instruction points at (part 3).
+pub struct SwapWithFirst;
+
+/// Moves the cursor and reflects it at both ends of the list.
+pub struct Bounce;
+
+impl MovementRule for Clamp {
+    fn apply(
+        &self,
+        names: &mut [String],
+        idx: usize,
+        instruction: &Instruction,
+    ) -> Result<usize, WheelError> {
+        let size = list_size(names.len())?;
+        // moving further than the list is long always ends at one of the ends
+        let steps = checked_steps(instruction)?.min(size);
+        let next = match instruction.direction {
+            Direction::Left => idx as i64 - steps,
+            Direction::Right => idx as i64 + steps,
+        };
+        Ok(next.clamp(0, size - 1) as usize)
+    }
+
+    fn index(&self, size: usize, index: i64) -> Result<usize, WheelError> {
+        let size = list_size(size)?;
+        Ok(index.clamp(0, size - 1) as usize)
+    }
+}
+
+impl MovementRule for Wrap {
+    fn apply(
+        &self,
+        names: &mut [String],
+        idx: usize,
+        instruction: &Instruction,
+    ) -> Result<usize, WheelError> {
+        let size = list_size(names.len())?;
+        Ok((idx as i64 + offset(instruction, size)?).rem_euclid(size) as usize)
+    }
+}
+
+impl SwapWithFirst {
+    /// Index the first name is swapped with in a list of `size` names.
+    pub fn target(&self, size: usize, instruction: &Instruction) -> Result<usize, WheelError> {
+        let size = list_size(size)?;
+        Ok(offset(instruction, size)? as usize)
+    }
+}
+
+impl MovementRule for SwapWithFirst {
+    fn apply(
+        &self,
+        names: &mut [String],
+        _idx: usize,
+        instruction: &Instruction,
+    ) -> Result<usize, WheelError> {
+        let target = self.target(names.len(), instruction)?;
+        names.swap(0, target);
+        Ok(target)
+    }
+
+    fn selected(&self, _cursor: usize) -> usize {
+        0
+    }
+
+    fn jump(&self, names: &mut [String], index: i64) -> Result<usize, WheelError> {
+        let target = self.index(names.len(), index)?;
+        names.swap(0, target);
+        Ok(target)
+    }
+}
+
+impl Bounce {
+    /// Folds `position`, counted as if the list went on past its ends, back
+    /// into a list of `size` names by reflecting it off both ends.
+    fn reflect(size: i64, position: i64) -> usize {
+        if size == 1 {
+            return 0;
+        }
+        let period = 2 * (size - 1);
+        let pos = position.rem_euclid(period);
+        if pos < size {
+            pos as usize
+        } else {
+            (period - pos) as usize
+        }
+    }
+}
+
+impl MovementRule for Bounce {
+    fn apply(
+        &self,
+        names: &mut [String],
+        idx: usize,
+        instruction: &Instruction,
+    ) -> Result<usize, WheelError> {
+        let size = list_size(names.len())?;
+        if size == 1 {
+            return Ok(0);
+        }
+        let period = 2 * (size - 1);
+        Ok(Self::reflect(
+            size,
+            idx as i64 + offset(instruction, period)?,
+        ))
+    }
+
+    fn index(&self, size: usize, index: i64) -> Result<usize, WheelError> {
+        let size = list_size(size)?;
+        Ok(Self::reflect(size, index))
+    }
+}
+
+/// Looks up one of the built-in rules by name.
+pub fn rule_by_name(name: &str) -> Option<Box<dyn MovementRule>> {
+    match name {
+        "clamp" => Some(Box::new(Clamp)),
+        "wrap" => Some(Box::new(Wrap)),
+        "swap" => Some(Box::new(SwapWithFirst)),
+        "bounce" => Some(Box::new(Bounce)),
+        _ => None,
+    }
+}
+
+/// Final state of a simulation.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub name: String,
+    pub names: Vec<String>,
+    /// Cursor positions, starting with the initial position and followed by
+    /// the position after each instruction.
+    pub history: Vec<usize>,
+}
+
+/// Applies all `instructions` to `names` under `rule`, starting at index 0.
+pub fn simulate(
+    rule: &(impl MovementRule + ?Sized),
+    names: &[String],
+    instructions: &[Instruction],
+) -> Result<Outcome, WheelError> {
+    simulate_observed(rule, names, instructions, |_, _| {})
+}
+
+/// Like [`simulate`], but calls `observe` with the names and the cursor
+/// before the first instruction and after each one.
+pub fn simulate_observed(
+    rule: &(impl MovementRule + ?Sized),
+    names: &[String],
+    instructions: &[Instruction],
+    mut observe: impl FnMut(&[String], usize),
+) -> Result<Outcome, WheelError> {
+    list_size(names.len())?;
+    let mut names = names.to_vec();
+    let mut history = Vec::with_capacity(instructions.len() + 1);
+    let mut idx = 0;
+    history.push(idx);
+    observe(&names, idx);
+    for instruction in instructions.iter() {
+        idx = rule.apply(&mut names, idx, instruction)?;
+        history.push(idx);
+        observe(&names, idx);
+    }
+    let name = names[rule.selected(idx)].clone();
+    Ok(Outcome {
+        name,
+        names,
+        history,
+    })
+}
+
+/// The length of a list of names as an `i64`, failing for empty lists.
+pub fn list_size(len: usize) -> Result<i64, WheelError> {
+    if len == 0 {
+        return Err(WheelError::EmptyNames);
+    }
+    i64::try_from(len).map_err(|_| WheelError::OutOfRange(format!("{len} names")))
+}
+
+fn checked_steps(instruction: &Instruction) -> Result<i64, WheelError> {
+    if instruction.steps < 0 {
+        return Err(WheelError::OutOfRange(format!("{instruction}")));
+    }
+    Ok(instruction.steps)
+}
+
+/// The signed movement of `instruction`, reduced modulo `size` into `0..size`.
+fn offset(instruction: &Instruction, size: i64) -> Result<i64, WheelError> {
+    let steps = checked_steps(instruction)? % size;
+    Ok(match instruction.direction {
+        Direction::Left => (size - steps) % size,
+        Direction::Right => steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("n{i}")).collect()
+    }
+
+    fn right(steps: i64) -> Instruction {
+        Instruction {
+            direction: Direction::Right,
+            steps,
+        }
+    }
+
+    fn left(steps: i64) -> Instruction {
+        Instruction {
+            direction: Direction::Left,
+            steps,
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let outcome = simulate(&Wrap, &names(4), &[right(3), left(2), right(3), left(1)]).unwrap();
+        assert_eq!(outcome.history, vec![0, 3, 1, 0, 3]);
+        assert_eq!(outcome.name, "n3");
+    }
+
+    #[test]
+    fn test_swap_with_first() {
+        let outcome = simulate(&SwapWithFirst, &names(4), &[right(3), left(1)]).unwrap();
+        assert_eq!(outcome.history, vec![0, 3, 3]);
+        assert_eq!(outcome.names, vec!["n0", "n1", "n2", "n3"]);
+        assert_eq!(outcome.name, "n0");
+    }
+
+    #[test]
+    fn test_bounce() {
+        let outcome =
+            simulate(&Bounce, &names(4), &[right(5), left(3), left(7), right(1)]).unwrap();
+        assert_eq!(outcome.history, vec![0, 1, 2, 1, 2]);
+        assert_eq!(outcome.name, "n2");
+    }
+
+    #[test]
+    fn test_huge_steps() {
+        let huge = i64::MAX;
+        for rule in ["clamp", "wrap", "swap", "bounce"] {
+            let rule = rule_by_name(rule).unwrap();
+            let outcome = simulate(rule.as_ref(), &names(5), &[right(huge), left(huge)]);
+            assert!(outcome.is_ok());
+        }
+        let outcome = simulate(&Wrap, &names(5), &[right(huge)]).unwrap();
+        assert_eq!(outcome.history, vec![0, (huge % 5) as usize]);
+        let outcome = simulate(&Clamp, &names(5), &[right(huge), left(huge - 1)]).unwrap();
+        assert_eq!(outcome.history, vec![0, 4, 0]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            simulate(&Wrap, &[], &[right(1)]).unwrap_err(),
+            WheelError::EmptyNames
+        );
+        assert!(matches!(
+            simulate(&Clamp, &names(3), &[right(-1)]),
+            Err(WheelError::OutOfRange(_))
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::rules::MovementRule;
+use crate::{Direction, Instruction};
+use common::graph::{ImplicitGraph, bfs_path};
+use std::cell::RefCell;
+
+/// State of the search: the cursor and the position of the target. The other
+/// names never decide whether the target is reached, so they are left out.
+type State = (usize, usize);
+
+/// Finds a shortest list of instructions that selects `target` under `rule`,
+/// using at most `max_steps` steps per instruction.
+///
+/// Every rule comes back to the same cursor within twice the number of names
+/// steps, so larger step counts are never tried.
+///
+/// Returns an empty list if `target` is selected without any instruction and
+/// `None` if it cannot be selected at all.
+pub fn shortest_instructions(
+    rule: &(impl MovementRule + ?Sized),
+    names: &[String],
+    target: &str,
+    max_steps: i64,
+) -> Option<Vec<Instruction>> {
+    let start = names.iter().position(|name| name == target)?;
+    let max_steps = max_steps.min(2 * names.len() as i64);
+    let candidates: Vec<Instruction> = (1..=max_steps)
+        .flat_map(|steps| {
+            [Direction::Right, Direction::Left].map(|direction| Instruction { direction, steps })
+        })
+        .collect();
+    // blank names with the target put back in for each move, so that moving
+    // does not copy the list
+    let scratch = RefCell::new(vec![String::new(); names.len()]);
+    let apply = |&(cursor, position): &State, instruction: &Instruction| -> Option<State> {
+        let mut names = scratch.borrow_mut();
+        names[position] = target.to_string();
+        let cursor = rule.apply(&mut names, cursor, instruction);
+        let position = names
+            .iter()
+            .position(|name| !name.is_empty())
+            .expect("rules only rearrange names");
+        names[position].clear();
+        Some((cursor.ok()?, position))
+    };
+    let graph = ImplicitGraph::new(|state: &State| {
+        candidates
+            .iter()
+            .filter_map(|instruction| apply(state, instruction))
+            .collect::<Vec<State>>()
+    });
+    let path = bfs_path(&graph, (0, start), |&(cursor, position)| {
+        rule.selected(cursor) == position
+    })?;
+    let result = path
+        .windows(2)
+        .map(|w| {
+            candidates
+                .iter()
+                .find(|instruction| apply(&w[0], instruction) == Some(w[1]))
+                .copied()
+                .expect("every step of the path comes from a candidate")
+        })
+        .collect();
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Clamp, SwapWithFirst, Wrap, simulate};
+
+    fn names() -> Vec<String> {
+        [
+            "Vyrdax", "Drakzyph", "Fyrryn", "Elarzris", "Xaryt", "Oronris",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn format_instructions(instructions: &[Instruction]) -> String {
+        instructions
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn test_clamp_needs_several_steps() {
+        let result = shortest_instructions(&Clamp, &names(), "Oronris", 2).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(simulate(&Clamp, &names(), &result).unwrap().name, "Oronris");
+    }
+
+    #[test]
+    fn test_wrap_goes_backwards() {
+        let result = shortest_instructions(&Wrap, &names(), "Oronris", 2).unwrap();
+        assert_eq!(format_instructions(&result), "L1");
+    }
+
+    #[test]
+    fn test_swap_with_first() {
+        let result = shortest_instructions(&SwapWithFirst, &names(), "Xaryt", 2).unwrap();
+        assert_eq!(format_instructions(&result), "L2");
+        assert_eq!(
+            simulate(&SwapWithFirst, &names(), &result).unwrap().name,
+            "Xaryt"
+        );
+        assert!(shortest_instructions(&SwapWithFirst, &names(), "Elarzris", 2).is_none());
+    }
+
+    #[test]
+    fn test_huge_step_limit() {
+        let result = shortest_instructions(&Clamp, &names(), "Oronris", i64::MAX).unwrap();
+        assert_eq!(format_instructions(&result), "R5");
+    }
+
+    #[test]
+    fn test_already_selected_or_missing() {
+        assert_eq!(
+            shortest_instructions(&Wrap, &names(), "Vyrdax", 3).map(|r| r.len()),
+            Some(0)
+        );
+        assert!(shortest_instructions(&Wrap, &names(), "Nobody", 3).is_none());
+    }
+}
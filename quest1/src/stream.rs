@@ -0,0 +1,226 @@
+use crate::rules::MovementRule;
+use crate::{parse_instruction, parse_names};
+use anyhow::{Context, anyhow, bail};
+use std::io::{BufRead, Write};
+use winnow::Parser;
+
+/// Longest instruction accepted by the streaming parser. Anything longer
+/// cannot be a valid `i64` step count and would make memory grow.
+const MAX_INSTRUCTION_LEN: usize = 24;
+
+/// Final state of a streamed simulation. Unlike [`crate::rules::Outcome`] it
+/// keeps no history, so memory does not grow with the number of instructions.
+#[derive(Debug, Clone)]
+pub struct StreamOutcome {
+    pub name: String,
+    pub names: Vec<String>,
+    pub cursor: usize,
+    pub instructions: u64,
+}
+
+/// Applies the instructions of a puzzle input read from `reader` under
+/// `rule`, one instruction at a time as the input is read.
+///
+/// Only the names and the instruction being parsed are kept in memory; the
+/// instruction section is consumed chunk by chunk straight from the reader's
+/// buffer. Like the batch parser, it takes one line of comma separated
+/// instructions followed only by whitespace.
+pub fn simulate_stream(
+    rule: &(impl MovementRule + ?Sized),
+    mut reader: impl BufRead,
+) -> anyhow::Result<StreamOutcome> {
+    // the names and the blank line after them
+    let mut header = String::new();
+    reader.read_line(&mut header)?;
+    reader.read_line(&mut header)?;
+    let mut names = parse_names
+        .parse(&header)
+        .map_err(|e| anyhow!("Invalid names: {e}"))?;
+
+    let mut cursor = 0;
+    let mut count = 0;
+    let mut token = Vec::with_capacity(MAX_INSTRUCTION_LEN);
+    let mut apply = |token: &[u8]| -> anyhow::Result<()> {
+        count += 1;
+        let text = String::from_utf8_lossy(token);
+        let instruction = parse_instruction
+            .parse(&text)
+            .map_err(|e| anyhow!("{e}"))
+            .with_context(|| format!("Instruction {count}: {text:?}"))?;
+        cursor = rule
+            .apply(&mut names, cursor, &instruction)
+            .with_context(|| format!("Instruction {count}: {text:?}"))?;
+        Ok(())
+    };
+    // set at the start and after each comma, until the next instruction begins
+    let mut expecting = true;
+    // set once whitespace ends the instructions, after which only more
+    // whitespace may follow
+    let mut ended = false;
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        for &byte in chunk {
+            match byte {
+                b',' if !ended => {
+                    if token.is_empty() {
+                        bail!("Instruction {} is missing", count + 1);
+                    }
+                    apply(&token)?;
+                    token.clear();
+                    expecting = true;
+                }
+                b' ' | b'\t' | b'\r' | b'\n' => {
+                    if !token.is_empty() {
+                        apply(&token)?;
+                        token.clear();
+                        expecting = false;
+                    }
+                    ended = true;
+                }
+                _ if ended => bail!("Unexpected input after instruction {count}"),
+                _ if token.len() == MAX_INSTRUCTION_LEN => {
+                    bail!("Instruction {} is too long", count + 1);
+                }
+                _ => {
+                    token.push(byte);
+                    expecting = false;
+                }
+            }
+        }
+        let len = chunk.len();
+        reader.consume(len);
+    }
+    if !token.is_empty() {
+        apply(&token)?;
+    } else if expecting {
+        bail!("Instruction {} is missing", count + 1);
+    }
+
+    let name = names[rule.selected(cursor)].clone();
+    Ok(StreamOutcome {
+        name,
+        names,
+        cursor,
+        instructions: count,
+    })
+}
+
+/// Writes a puzzle input with `names` names and `instructions` random
+/// instructions, for feeding [`simulate_stream`] with arbitrarily large
+/// inputs. The same `seed` always produces the same input.
+pub fn write_generated(
+    mut writer: impl Write,
+    names: usize,
+    instructions: u64,
+    seed: u64,
+) -> std::io::Result<()> {
+    let names: Vec<String> = (0..names.max(1)).map(generated_name).collect();
+    writeln!(writer, "{}", names.join(","))?;
+    writeln!(writer)?;
+    // xorshift64, which must not start at zero
+    let mut state = seed.max(1);
+    for i in 0..instructions {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let direction = if state & 1 == 0 { 'L' } else { 'R' };
+        let steps = (state >> 1) % 20 + 1;
+        if i > 0 {
+            write!(writer, ",")?;
+        }
+        write!(writer, "{direction}{steps}")?;
+    }
+    writeln!(writer)
+}
+
+/// A unique, purely alphabetic name for index `i`: `Na`, `Nb`, ..., `Nba`, ...
+fn generated_name(mut i: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'a' + (i % 26) as u8);
+        i /= 26;
+        if i == 0 {
+            break;
+        }
+    }
+    letters.push(b'N');
+    letters.reverse();
+    String::from_utf8(letters).expect("ascii letters")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input_data;
+    use crate::rules::{Clamp, SwapWithFirst, Wrap, rule_by_name, simulate};
+    use std::io::BufReader;
+
+    const INPUT: &str =
+        "Vyrdax,Drakzyph,Fyrryn,Elarzris,Xaryt,Oronris\r\n\r\nR3,L2,R8,L1,R4,L5,R2\n";
+
+    #[test]
+    fn test_small_chunks_match_simulation() {
+        let mut inp = INPUT;
+        let input_data = parse_input_data(&mut inp).unwrap();
+        for rule in ["clamp", "wrap", "swap", "bounce"] {
+            let rule = rule_by_name(rule).unwrap();
+            let expected =
+                simulate(rule.as_ref(), &input_data.names, &input_data.instructions).unwrap();
+            // a three byte buffer splits most instructions across chunks
+            let reader = BufReader::with_capacity(3, INPUT.as_bytes());
+            let outcome = simulate_stream(rule.as_ref(), reader).unwrap();
+            assert_eq!(outcome.name, expected.name);
+            assert_eq!(outcome.names, expected.names);
+            assert_eq!(outcome.cursor, *expected.history.last().unwrap());
+            assert_eq!(outcome.instructions, 7);
+        }
+    }
+
+    #[test]
+    fn test_generated_input() {
+        let mut generated = Vec::new();
+        write_generated(&mut generated, 30, 1000, 42).unwrap();
+        let text = String::from_utf8(generated).unwrap();
+        let mut inp = text.as_str();
+        let input_data = parse_input_data(&mut inp).unwrap();
+        assert_eq!(input_data.names.len(), 30);
+        assert_eq!(input_data.instructions.len(), 1000);
+        let expected = simulate(&SwapWithFirst, &input_data.names, &input_data.instructions);
+        let outcome = simulate_stream(&SwapWithFirst, text.as_bytes()).unwrap();
+        assert_eq!(outcome.names, expected.unwrap().names);
+    }
+
+    #[test]
+    fn test_errors_name_the_instruction() {
+        let error = simulate_stream(&Wrap, "Ab,Cd\n\nR1,X2".as_bytes()).unwrap_err();
+        assert!(format!("{error:#}").starts_with("Instruction 2: \"X2\""));
+        let long = format!("Ab,Cd\n\nR1,R{}", "9".repeat(40));
+        let error = simulate_stream(&Clamp, long.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "Instruction 2 is too long");
+        assert!(simulate_stream(&Clamp, "Ab,Cd\nR1".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_what_the_batch_parser_rejects() {
+        let error = |input: &str| {
+            let mut inp = input;
+            assert!(parse_input_data(&mut inp).is_err(), "{input:?}");
+            simulate_stream(&Wrap, input.as_bytes())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(error("Ab,Cd\n\nR1,,R2\n"), "Instruction 2 is missing");
+        assert_eq!(
+            error("Ab,Cd\n\nR1\nR2\n"),
+            "Unexpected input after instruction 1"
+        );
+        assert_eq!(error("Ab,Cd\n\n"), "Instruction 1 is missing");
+        assert_eq!(error("Ab,Cd\n\n\n"), "Instruction 1 is missing");
+        assert_eq!(error("Ab,Cd\n\nR1,R2,\n"), "Instruction 3 is missing");
+        let outcome = simulate_stream(&Wrap, "Ab,Cd\n\nR1,R2 \r\n\n".as_bytes()).unwrap();
+        assert_eq!(outcome.instructions, 2);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::complex::Complex;
+use crate::config::EngraveConfig;
+
+/// Number of points advanced together. Small enough for the lanes of a batch
+/// to stay in the L1 cache.
+const BATCH: usize = 1024;
+
+/// The points of a batch that have not escaped yet, as a structure of arrays
+/// so that one cycle is a plain loop over slices of `i64`.
+struct Lanes {
+    /// Current result.
+    x: Vec<i64>,
+    y: Vec<i64>,
+    /// The point being engraved.
+    px: Vec<i64>,
+    py: Vec<i64>,
+    /// Index of the point in the input.
+    index: Vec<usize>,
+}
+
+impl Lanes {
+    fn new(points: &[Complex], offset: usize) -> Self {
+        Lanes {
+            x: vec![0; points.len()],
+            y: vec![0; points.len()],
+            px: points.iter().map(|p| p.x).collect(),
+            py: points.iter().map(|p| p.y).collect(),
+            index: (offset..offset + points.len()).collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Runs one cycle on every lane.
+    fn advance(&mut self, divisor: Complex) {
+        let n = self.len();
+        let (x, y) = (&mut self.x[..n], &mut self.y[..n]);
+        let (px, py) = (&self.px[..n], &self.py[..n]);
+        for i in 0..n {
+            let (a, b) = (x[i], y[i]);
+            x[i] = (a * a - b * b) / divisor.x + px[i];
+            y[i] = (a * b + b * a) / divisor.y + py[i];
+        }
+    }
+
+    /// Drops the lanes that escaped `bound`, calling `escaped` with the index
+    /// of each. The remaining lanes keep their order.
+    fn retain_inside(&mut self, bound: i64, mut escaped: impl FnMut(usize)) {
+        let mut kept = 0;
+        for i in 0..self.len() {
+            if (-bound..=bound).contains(&self.x[i]) && (-bound..=bound).contains(&self.y[i]) {
+                self.x[kept] = self.x[i];
+                self.y[kept] = self.y[i];
+                self.px[kept] = self.px[i];
+                self.py[kept] = self.py[i];
+                self.index[kept] = self.index[i];
+                kept += 1;
+            } else {
+                escaped(self.index[i]);
+            }
+        }
+        for lane in [&mut self.x, &mut self.y, &mut self.px, &mut self.py] {
+            lane.truncate(kept);
+        }
+        self.index.truncate(kept);
+    }
+}
+
+/// [`should_plot`](crate::should_plot) for many points at once.
+///
+/// The points are split into batches whose lanes run the cycles in lockstep.
+/// Lanes that escape are dropped after each cycle, so a batch gets cheaper as
+/// it goes and stops once all its points escaped. Unlike `should_plot` this
+/// does not look for repeated values; every surviving lane runs all cycles.
+pub fn plot_batch(points: &[Complex], config: &EngraveConfig) -> Vec<bool> {
+    let mut engraved = vec![true; points.len()];
+    for (i, chunk) in points.chunks(BATCH).enumerate() {
+        let mut lanes = Lanes::new(chunk, i * BATCH);
+        for _ in 0..config.cycles {
+            if lanes.len() == 0 {
+                break;
+            }
+            // the components stay within the validated bound, so this cannot
+            // overflow
+            lanes.advance(config.divisor);
+            lanes.retain_inside(config.bound, |index| engraved[index] = false);
+        }
+    }
+    engraved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sample, should_plot};
+    use common::geometry::Point;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_matches_should_plot_on_part2() {
+        let a = Complex::new(35300, -64910);
+        let config = EngraveConfig::PART2;
+        let points: Vec<Complex> = (0..config.side() as i64)
+            .flat_map(|y| (0..config.side() as i64).map(move |x| (x, y)))
+            .map(|(x, y)| sample(&a, &config, Point::new(x, y)))
+            .collect();
+        let engraved = plot_batch(&points, &config);
+        assert_eq!(engraved.iter().filter(|e| **e).count(), 4076);
+        for (p, engraved) in points.iter().zip(engraved) {
+            assert_eq!(engraved, should_plot(p, &config), "{p}");
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(plot_batch(&[], &EngraveConfig::PART3).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_should_plot(
+            points in prop::collection::vec((-2_000_000i64..2_000_000, -2_000_000i64..2_000_000), 0..3000),
+            cycles in 0u32..120,
+        ) {
+            let config = EngraveConfig { cycles, ..EngraveConfig::PART3 };
+            let points: Vec<Complex> = points.into_iter().map(|(x, y)| Complex::new(x, y)).collect();
+            let expected: Vec<bool> = points.iter().map(|p| should_plot(p, &config)).collect();
+            prop_assert_eq!(plot_batch(&points, &config), expected);
+        }
+    }
+}
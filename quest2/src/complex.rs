@@ -0,0 +1,282 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// The integer types usable as components of a [`Complex`].
+pub trait Component:
+    Copy
+    + Eq
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    const ZERO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    /// `a * b + c * d`, or `None` if the exact result does not fit.
+    fn checked_mul_add(a: Self, b: Self, c: Self, d: Self) -> Option<Self>;
+    /// `a * b - c * d`, or `None` if the exact result does not fit.
+    fn checked_mul_sub(a: Self, b: Self, c: Self, d: Self) -> Option<Self>;
+}
+
+/// Implements [`Component`] for `$t`, computing sums of products in `$wide`
+/// so that a product overflowing `$t` does not fail a result that fits. With
+/// no wider type, as for `i128`, `$wide` is `$t` itself and an overflowing
+/// product fails the result.
+macro_rules! impl_component {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl Component for $t {
+                const ZERO: Self = 0;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_div(self, other: Self) -> Option<Self> {
+                    <$t>::checked_div(self, other)
+                }
+
+                fn checked_mul_add(a: Self, b: Self, c: Self, d: Self) -> Option<Self> {
+                    let wide = <$wide>::from;
+                    let sum = wide(a)
+                        .checked_mul(wide(b))?
+                        .checked_add(wide(c).checked_mul(wide(d))?)?;
+                    Self::try_from(sum).ok()
+                }
+
+                fn checked_mul_sub(a: Self, b: Self, c: Self, d: Self) -> Option<Self> {
+                    let wide = <$wide>::from;
+                    let difference = wide(a)
+                        .checked_mul(wide(b))?
+                        .checked_sub(wide(c).checked_mul(wide(d))?)?;
+                    Self::try_from(difference).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_component!(i32 => i64, i64 => i128, i128 => i128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    /// The result of the named operation does not fit the component type.
+    Overflow(&'static str),
+    DivisionByZero,
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticError::Overflow(operation) => write!(f, "overflow in {operation}"),
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+/// A complex number with integer components, written `[x,y]`.
+///
+/// Addition and multiplication are the usual complex operations. Division is
+/// the puzzle's own rule and *not* complex division: each component is
+/// divided by the matching component of the divisor, truncating toward zero
+/// like Rust's integer division, so `[-7,7] / [2,2] = [-3,3]`.
+///
+/// The operators overflow like the component type does (panicking in debug
+/// builds, wrapping in release builds); the `checked_*` methods report
+/// overflow and division by zero as an [`ArithmeticError`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Complex<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Component> Complex<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Complex { x, y }
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, ArithmeticError> {
+        let overflow = || ArithmeticError::Overflow("addition");
+        Ok(Complex {
+            x: self.x.checked_add(other.x).ok_or_else(overflow)?,
+            y: self.y.checked_add(other.y).ok_or_else(overflow)?,
+        })
+    }
+
+    /// Fails only if a component of the exact product does not fit `T`,
+    /// except for `i128`, where the partial products must fit as well.
+    pub fn checked_mul(self, other: Self) -> Result<Self, ArithmeticError> {
+        let overflow = || ArithmeticError::Overflow("multiplication");
+        Ok(Complex {
+            x: T::checked_mul_sub(self.x, other.x, self.y, other.y).ok_or_else(overflow)?,
+            y: T::checked_mul_add(self.x, other.y, self.y, other.x).ok_or_else(overflow)?,
+        })
+    }
+
+    pub fn checked_div(self, other: Self) -> Result<Self, ArithmeticError> {
+        if other.x == T::ZERO || other.y == T::ZERO {
+            return Err(ArithmeticError::DivisionByZero);
+        }
+        let overflow = || ArithmeticError::Overflow("division");
+        Ok(Complex {
+            x: self.x.checked_div(other.x).ok_or_else(overflow)?,
+            y: self.y.checked_div(other.y).ok_or_else(overflow)?,
+        })
+    }
+}
+
+impl<T: Component> Add for Complex<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Complex {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<T: Component> Mul for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Complex {
+            x: self.x * other.x - self.y * other.y,
+            y: self.x * other.y + self.y * other.x,
+        }
+    }
+}
+
+impl<T: Component> Div for Complex<T> {
+    type Output = Self;
+
+    /// Divides component by component, truncating toward zero.
+    fn div(self, other: Self) -> Self {
+        Complex {
+            x: self.x / other.x,
+            y: self.y / other.y,
+        }
+    }
+}
+
+impl From<Complex<i64>> for Complex<i128> {
+    fn from(c: Complex<i64>) -> Self {
+        Complex {
+            x: c.x.into(),
+            y: c.y.into(),
+        }
+    }
+}
+
+impl TryFrom<Complex<i128>> for Complex<i64> {
+    type Error = ArithmeticError;
+
+    fn try_from(c: Complex<i128>) -> Result<Self, ArithmeticError> {
+        let overflow = |_| ArithmeticError::Overflow("conversion to i64");
+        Ok(Complex {
+            x: c.x.try_into().map_err(overflow)?,
+            y: c.y.try_into().map_err(overflow)?,
+        })
+    }
+}
+
+impl<T: Component> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{},{}]", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Exact results computed in `i128`, which cannot overflow for `i64`
+    /// operands.
+    fn reference_mul(a: Complex<i64>, b: Complex<i64>) -> Complex<i128> {
+        let (a, b) = (Complex::<i128>::from(a), Complex::<i128>::from(b));
+        a * b
+    }
+
+    /// Components below 2^31 in magnitude, so that a sum of two products
+    /// stays below 2^63 and fits an `i64`.
+    fn small() -> impl Strategy<Value = Complex<i64>> {
+        let range = -(1i64 << 31) + 1..(1 << 31);
+        (range.clone(), range).prop_map(|(x, y)| Complex::new(x, y))
+    }
+
+    fn any_complex() -> impl Strategy<Value = Complex<i64>> {
+        (any::<i64>(), any::<i64>()).prop_map(|(x, y)| Complex::new(x, y))
+    }
+
+    #[test]
+    fn test_truncating_division() {
+        let c = Complex::new(-7, 7) / Complex::new(2, 2);
+        assert_eq!(c, Complex::new(-3, 3));
+        assert_eq!(
+            Complex::new(1, 1).checked_div(Complex::new(1, 0)),
+            Err(ArithmeticError::DivisionByZero)
+        );
+        assert_eq!(
+            Complex::new(i64::MIN, 0).checked_div(Complex::new(-1, 1)),
+            Err(ArithmeticError::Overflow("division"))
+        );
+    }
+
+    #[test]
+    fn test_product_fits_although_a_partial_product_does_not() {
+        // 2^32 * 2^31 overflows, but subtracting 1 * 1 brings x back in range
+        let (a, b) = (Complex::new(1i64 << 32, 1), Complex::new(1 << 31, 1));
+        assert_eq!(
+            a.checked_mul(b),
+            Ok(Complex::new(i64::MAX, (1 << 32) + (1 << 31)))
+        );
+        let c = Complex::new(i32::MIN, i32::MIN);
+        assert_eq!(
+            c.checked_mul(Complex::new(1, -1)),
+            Err(ArithmeticError::Overflow("multiplication"))
+        );
+        assert_eq!(
+            Complex::new(-(1i64 << 31), 0).checked_mul(Complex::new(-(1 << 31), 0)),
+            Ok(Complex::new(1 << 62, 0))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn checked_ops_match_i128_reference(a in any_complex(), b in any_complex()) {
+            let wide = |c: Complex<i64>| Complex::<i128>::from(c);
+            // errors differ in the operation they name, so compare values only
+            let expected = Complex::try_from(wide(a) + wide(b)).ok();
+            prop_assert_eq!(a.checked_add(b).ok(), expected);
+            let expected = Complex::try_from(reference_mul(a, b)).ok();
+            prop_assert_eq!(a.checked_mul(b).ok(), expected);
+            if b.x != 0 && b.y != 0 {
+                let expected = Complex::try_from(wide(a) / wide(b)).ok();
+                prop_assert_eq!(a.checked_div(b).ok(), expected);
+            }
+        }
+
+        #[test]
+        fn operators_match_checked_ops_without_overflow(a in small(), b in small()) {
+            prop_assert_eq!(a.checked_add(b), Ok(a + b));
+            prop_assert_eq!(a.checked_mul(b), Ok(a * b));
+            prop_assert_eq!(Complex::try_from(reference_mul(a, b)), Ok(a * b));
+            if b.x != 0 && b.y != 0 {
+                prop_assert_eq!(a.checked_div(b), Ok(a / b));
+            }
+        }
+
+        #[test]
+        fn i128_variant_is_exact(a in any_complex(), b in any_complex()) {
+            let (wa, wb) = (Complex::<i128>::from(a), Complex::<i128>::from(b));
+            prop_assert_eq!(wa.checked_mul(wb), Ok(reference_mul(a, b)));
+            prop_assert!(wa.checked_add(wb).is_ok());
+        }
+    }
+}
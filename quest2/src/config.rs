@@ -0,0 +1,294 @@
+//! Parameters of the engraving, with presets for the three parts.
+//!
+//! Configurations can be read from files with one `key = value` per line:
+//!
+//! ```text
+//! # start from part 2 and halve the cycles
+//! preset = part2
+//! cycles = 50
+//! divisor = [100000,100000]
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. The same keys can be
+//! given on the command line as `--cycles 50` and so on.
+
+use crate::complex::Complex;
+use crate::{parse_complex, parse_int};
+use anyhow::anyhow;
+use winnow::Parser;
+
+/// Keys accepted by [`EngraveConfig::set`].
+pub const KEYS: [&str; 6] = ["preset", "cycles", "divisor", "bound", "size", "step"];
+
+/// Largest escape bound for which no cycle can overflow an `i64`: the
+/// components start each cycle within the bound, so squaring stays below
+/// `2 * bound²`.
+const MAX_BOUND: i64 = i32::MAX as i64;
+
+/// Largest grid size, which keeps the side of the grid and the offsets of
+/// its points far from overflowing.
+const MAX_SIZE: i64 = 100_000;
+
+/// A `key = value` setting, with its line number if it comes from a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Setting<'a> {
+    pub line: Option<usize>,
+    pub key: &'a str,
+    pub value: &'a str,
+}
+
+/// The settings of a configuration file, in order.
+pub fn read_settings(input: &str) -> anyhow::Result<Vec<Setting<'_>>> {
+    let mut settings = Vec::new();
+    for (line_no, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("line {}: expected key = value '{line}'", line_no + 1))?;
+        settings.push(Setting {
+            line: Some(line_no + 1),
+            key: key.trim(),
+            value: value.trim(),
+        });
+    }
+    Ok(settings)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngraveConfig {
+    /// Number of cycles to run.
+    pub cycles: u32,
+    /// What the square of the result is divided by in each cycle.
+    pub divisor: Complex,
+    /// A point escapes once a component leaves `-bound..=bound`.
+    pub bound: i64,
+    /// The grid covers offsets `0..=size` from A in both directions.
+    pub size: i64,
+    /// Distance between sampled points of the grid.
+    pub step: i64,
+}
+
+impl EngraveConfig {
+    /// Part 1 only runs `cycles` cycles with `divisor`; the other fields are
+    /// unused.
+    pub const PART1: Self = EngraveConfig {
+        cycles: 3,
+        divisor: Complex { x: 10, y: 10 },
+        bound: 1_000_000,
+        size: 0,
+        step: 1,
+    };
+
+    pub const PART2: Self = EngraveConfig {
+        cycles: 100,
+        divisor: Complex {
+            x: 100000,
+            y: 100000,
+        },
+        bound: 1_000_000,
+        size: 1000,
+        step: 10,
+    };
+
+    pub const PART3: Self = EngraveConfig {
+        step: 1,
+        ..Self::PART2
+    };
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "part1" | "1" => Some(Self::PART1),
+            "part2" | "2" => Some(Self::PART2),
+            "part3" | "3" => Some(Self::PART3),
+            _ => None,
+        }
+    }
+
+    /// Sets `key` to `value`. Setting `preset` replaces every field.
+    pub fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        let invalid = |e| anyhow!("Invalid value for {key}: {value} ({e})");
+        match key {
+            "preset" => {
+                *self = Self::preset(value).ok_or_else(|| anyhow!("Unknown preset: {value}"))?
+            }
+            "cycles" => {
+                self.cycles = value
+                    .parse()
+                    .map_err(|e: std::num::ParseIntError| invalid(e.to_string()))?
+            }
+            "divisor" => {
+                self.divisor = parse_complex
+                    .parse(value)
+                    .map_err(|e| invalid(e.to_string()))?
+            }
+            "bound" => self.bound = parse_int.parse(value).map_err(|e| invalid(e.to_string()))?,
+            "size" => self.size = parse_int.parse(value).map_err(|e| invalid(e.to_string()))?,
+            "step" => self.step = parse_int.parse(value).map_err(|e| invalid(e.to_string()))?,
+            _ => return Err(anyhow!("Unknown key {key}, expected one of {KEYS:?}")),
+        }
+        Ok(())
+    }
+
+    /// Applies `settings` on top of `self` and validates the result.
+    ///
+    /// A preset replaces every field, so presets go first whatever their
+    /// position, the last one winning, and the other keys after them in
+    /// order.
+    pub fn apply(mut self, settings: &[Setting]) -> anyhow::Result<Self> {
+        let (presets, others): (Vec<&Setting>, Vec<&Setting>) =
+            settings.iter().partition(|setting| setting.key == "preset");
+        for setting in presets.into_iter().chain(others) {
+            self.set(setting.key, setting.value)
+                .map_err(|e| match setting.line {
+                    Some(line) => anyhow!("line {line}: {e}"),
+                    None => e,
+                })?;
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.divisor.x == 0 || self.divisor.y == 0 {
+            return Err(anyhow!("The divisor {} has a zero component", self.divisor));
+        }
+        if !(0..=MAX_BOUND).contains(&self.bound) {
+            return Err(anyhow!("The bound must be within 0..={MAX_BOUND}"));
+        }
+        if !(0..=MAX_SIZE).contains(&self.size) {
+            return Err(anyhow!("The size must be within 0..={MAX_SIZE}"));
+        }
+        if self.step <= 0 {
+            return Err(anyhow!("The step must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Number of sampled points along each side of the grid.
+    pub fn side(&self) -> usize {
+        (self.size / self.step + 1) as usize
+    }
+
+    /// Checks that every sampled point of the grid anchored at `a` fits in an
+    /// `i64`. Sampled points lie at offsets `0..=size` from `a`, so only the
+    /// anchor can push them out of range.
+    pub fn check_anchor(&self, a: &Complex) -> anyhow::Result<()> {
+        let corner = Complex::new(self.size, self.size);
+        a.checked_add(corner)
+            .map(|_| ())
+            .map_err(|e| anyhow!("The grid of size {} at {a} is out of range: {e}", self.size))
+    }
+
+    /// Whether `r` lies outside the escape bounds.
+    pub fn escaped(&self, r: &Complex) -> bool {
+        let bounds = -self.bound..=self.bound;
+        !bounds.contains(&r.x) || !bounds.contains(&r.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: EngraveConfig, input: &str) -> anyhow::Result<EngraveConfig> {
+        config.apply(&read_settings(input)?)
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(EngraveConfig::preset("part3").unwrap().side(), 1001);
+        assert_eq!(EngraveConfig::preset("2").unwrap().side(), 101);
+        assert!(EngraveConfig::preset("part4").is_none());
+        for preset in [
+            EngraveConfig::PART1,
+            EngraveConfig::PART2,
+            EngraveConfig::PART3,
+        ] {
+            assert!(preset.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_fixture() {
+        let config = parse(EngraveConfig::PART1, include_str!("../fixtures/coarse.cfg")).unwrap();
+        assert_eq!(
+            config,
+            EngraveConfig {
+                cycles: 50,
+                step: 20,
+                ..EngraveConfig::PART2
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let mut config = EngraveConfig::PART2;
+        assert!(config.set("cycles", "-1").is_err());
+        assert!(config.set("colour", "red").is_err());
+        config.set("divisor", "[3,-4]").unwrap();
+        assert_eq!(config.divisor, Complex::new(3, -4));
+        let error = parse(EngraveConfig::PART2, "divisor = [0,1]").unwrap_err();
+        assert!(error.to_string().contains("zero component"));
+        let error = parse(EngraveConfig::PART2, "\nstep 3").unwrap_err();
+        assert!(error.to_string().starts_with("line 2:"));
+        assert!(parse(EngraveConfig::PART2, "bound = 3037000500").is_err());
+        assert!(parse(EngraveConfig::PART2, "size = 100000").is_ok());
+        let error = parse(EngraveConfig::PART2, "size = 100001").unwrap_err();
+        assert!(error.to_string().contains("0..=100000"));
+    }
+
+    #[test]
+    fn test_check_anchor() {
+        let config = EngraveConfig::PART3;
+        assert!(
+            config
+                .check_anchor(&Complex::new(i64::MAX - 1000, 0))
+                .is_ok()
+        );
+        assert!(
+            config
+                .check_anchor(&Complex::new(0, i64::MAX - 999))
+                .is_err()
+        );
+        assert!(
+            config
+                .check_anchor(&Complex::new(i64::MIN, i64::MIN))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_preset_goes_first() {
+        let settings = [
+            Setting {
+                line: None,
+                key: "cycles",
+                value: "5",
+            },
+            Setting {
+                line: None,
+                key: "preset",
+                value: "part1",
+            },
+            Setting {
+                line: None,
+                key: "preset",
+                value: "part3",
+            },
+        ];
+        let config = EngraveConfig::PART2.apply(&settings).unwrap();
+        assert_eq!(
+            config,
+            EngraveConfig {
+                cycles: 5,
+                ..EngraveConfig::PART3
+            }
+        );
+        let error = parse(EngraveConfig::PART2, "cycles = 5\npreset = 4").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: Unknown preset: 4");
+    }
+}
@@ -0,0 +1,148 @@
+use common::grid::Grid;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Characters for escaped points in shaded ASCII art, from points that
+/// escaped at once to points that almost stayed.
+const SHADES: &[u8] = b" .:-=+*#%";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `X` for engraved points and `.` (or a shade) for the others.
+    Ascii,
+    /// Binary greyscale image.
+    Pgm,
+    /// Binary colour image.
+    Ppm,
+}
+
+impl Format {
+    /// Picks the format from the extension of `path`, defaulting to ASCII.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("pgm") => Format::Pgm,
+            Some("ppm") => Format::Ppm,
+            _ => Format::Ascii,
+        }
+    }
+}
+
+/// Writes an evaluated grid, where each cell holds the cycle in which the
+/// point escaped or `None` if it is engraved.
+///
+/// Engraved points are black (or `X`). With `shade`, the other points get
+/// darker the more of the `cycles` cycles they survived; otherwise they are
+/// white (or `.`).
+pub fn write_image(
+    grid: &Grid<Option<u32>>,
+    format: Format,
+    shade: bool,
+    cycles: u32,
+    mut out: impl Write,
+) -> io::Result<()> {
+    // fraction of the cycles survived by an escaped point, 0 when not shading
+    let survived = |escaped: u32| {
+        if shade {
+            f64::from(escaped - 1) / f64::from(cycles.max(1))
+        } else {
+            0.0
+        }
+    };
+    match format {
+        Format::Ascii => {
+            let art = grid.render(|cell| match cell {
+                None => 'X',
+                Some(_) if !shade => '.',
+                Some(escaped) => {
+                    let level = survived(*escaped) * (SHADES.len() - 1) as f64;
+                    SHADES[level.round() as usize] as char
+                }
+            });
+            write!(out, "{art}")
+        }
+        Format::Pgm => {
+            write!(out, "P5\n{} {}\n255\n", grid.width(), grid.height())?;
+            let pixels: Vec<u8> = grid
+                .iter()
+                .map(|(_, cell)| match cell {
+                    None => 0,
+                    Some(escaped) => (255.0 - 200.0 * survived(*escaped)).round() as u8,
+                })
+                .collect();
+            out.write_all(&pixels)
+        }
+        Format::Ppm => {
+            write!(out, "P6\n{} {}\n255\n", grid.width(), grid.height())?;
+            let pixels: Vec<u8> = grid
+                .iter()
+                .flat_map(|(_, cell)| match cell {
+                    None => [0, 0, 0],
+                    Some(escaped) => gradient(survived(*escaped)),
+                })
+                .collect();
+            out.write_all(&pixels)
+        }
+    }
+}
+
+/// Colour for `t` in `0.0..=1.0`, from white through yellow and orange to
+/// dark red.
+fn gradient(t: f64) -> [u8; 3] {
+    const STOPS: [[f64; 3]; 4] = [
+        [255.0, 255.0, 255.0],
+        [255.0, 220.0, 60.0],
+        [230.0, 110.0, 20.0],
+        [120.0, 10.0, 10.0],
+    ];
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (scaled.floor() as usize).min(STOPS.len() - 2);
+    let f = scaled - i as f64;
+    let (a, b) = (STOPS[i], STOPS[i + 1]);
+    [0, 1, 2].map(|c| (a[c] + (b[c] - a[c]) * f).round() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<Option<u32>> {
+        Grid::from_fn(3, 2, |p| match (p.x, p.y) {
+            (1, _) => None,
+            (0, 0) => Some(1),
+            _ => Some(100),
+        })
+    }
+
+    fn render(format: Format, shade: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_image(&sample(), format, shade, 100, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert_eq!(render(Format::Ascii, false), b".X.\n.X.\n");
+        assert_eq!(render(Format::Ascii, true), b" X%\n%X%\n");
+    }
+
+    #[test]
+    fn test_netpbm() {
+        let pgm = render(Format::Pgm, true);
+        assert!(pgm.starts_with(b"P5\n3 2\n255\n"));
+        assert_eq!(&pgm[pgm.len() - 6..], [255, 0, 57, 57, 0, 57]);
+        let ppm = render(Format::Ppm, false);
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(
+            &ppm[ppm.len() - 9..],
+            [255, 255, 255, 0, 0, 0, 255, 255, 255]
+        );
+        assert_eq!(gradient(1.0), [120, 10, 10]);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a/b.pgm")), Format::Pgm);
+        assert_eq!(Format::from_path(Path::new("b.ppm")), Format::Ppm);
+        assert_eq!(Format::from_path(Path::new("b.txt")), Format::Ascii);
+    }
+}
@@ -1,128 +1,548 @@
+mod batch;
+mod complex;
+mod config;
+mod image;
+mod orbit;
+mod parallel;
+mod region;
+mod stats;
+
 use anyhow::anyhow;
-use std::fmt;
+use batch::plot_batch;
+use common::explain::{EXPLAIN_FLAG, Explain};
+use common::geometry::{Point, Rect};
+use common::grid::Grid;
+use common::input::read_input;
+use complex::Complex;
+use config::{EngraveConfig, KEYS, Setting, read_settings};
+use image::{Format, write_image};
+use orbit::{Check, Orbit};
+use parallel::{default_threads, evaluate_rows};
+use region::{Query, RegionCache};
+use stats::EscapeStats;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
+use std::time::Instant;
 use winnow::ModalResult;
 use winnow::Parser;
 use winnow::ascii::{digit1, multispace0};
 use winnow::combinator::{delimited, eof, opt, separated_pair};
 
+const USAGE: &str = "Usage:
+  quest2 [--explain] [options]             solve all parts
+  quest2 render <input> <output> [--shade] [options]
+                                           draw the grid as .pgm, .ppm or ASCII art (- for stdout)
+  quest2 bench <input> [options]           time the grid on one thread and on all threads,
+                                           and batched against point by point
+  quest2 savings <input> [options]         count the cycles skipped by detecting repeats
+  quest2 stats <input> [--boundary] [options]
+                                           summarize when points escape, optionally listing
+                                           the engraved points next to escaped ones
+  quest2 orbit <input> [<dx> <dy>] [--part1] [--csv] [options]
+                                           trace the cycles of A + [dx,dy] step by step, or of A
+                                           as in part 1
+  quest2 query <input> (<x1> <y1> <x2> <y2> <step>)... [--list] [options]
+                                           count the engraved points of rectangles given as
+                                           offsets from A, reusing points shared between them
+
+Options:
+  --threads <n>                            threads evaluating grids (default: one per core)
+  --config <file>                          read settings from a file of key = value lines
+  --preset <part1|part2|part3>             start from the parameters of a part
+  --cycles <n> --divisor <[x,y]> --bound <n> --size <n> --step <n>
+                                           override single parameters
+
+Solving all parts applies the settings only to the part chosen with --preset, either
+on the command line or in the config file, and keeps the puzzle parameters for the
+others; settings without a preset are an error. Presets apply before the other
+settings. The other commands start from part3.";
+
+/// Command line settings shared by all commands.
+struct Options {
+    threads: usize,
+    /// Contents of the `--config` file.
+    config_file: Option<String>,
+    /// `--key value` settings, in the order given.
+    settings: Vec<(String, String)>,
+}
+
+impl Options {
+    /// Removes the options from `args`, leaving the command and its arguments.
+    fn take(args: &mut Vec<String>) -> anyhow::Result<Self> {
+        let mut options = Options {
+            threads: default_threads(),
+            config_file: None,
+            settings: Vec::new(),
+        };
+        let mut i = 0;
+        while i < args.len() {
+            let Some(key) = args[i].strip_prefix("--") else {
+                i += 1;
+                continue;
+            };
+            if key != "threads" && key != "config" && !KEYS.contains(&key) {
+                i += 1;
+                continue;
+            }
+            let value = args.get(i + 1).ok_or_else(|| anyhow!("{USAGE}"))?;
+            match key {
+                "threads" => options.threads = value.parse()?,
+                "config" => options.config_file = Some(std::fs::read_to_string(value)?),
+                _ => options.settings.push((key.to_string(), value.clone())),
+            }
+            args.drain(i..i + 2);
+        }
+        Ok(options)
+    }
+
+    /// The settings of the config file followed by those of the command line.
+    fn settings(&self) -> anyhow::Result<Vec<Setting<'_>>> {
+        let mut settings = match &self.config_file {
+            Some(contents) => read_settings(contents)?,
+            None => Vec::new(),
+        };
+        settings.extend(self.settings.iter().map(|(key, value)| Setting {
+            line: None,
+            key,
+            value,
+        }));
+        Ok(settings)
+    }
+
+    /// Applies the config file and the settings on top of `preset`.
+    fn config(&self, preset: EngraveConfig) -> anyhow::Result<EngraveConfig> {
+        preset.apply(&self.settings()?)
+    }
+
+    /// The parameters of a puzzle part: `preset` with the settings applied if
+    /// the user chose it, and `preset` as is otherwise. Settings without a
+    /// chosen preset are an error, as they would not apply to any part.
+    fn part_config(&self, preset: EngraveConfig) -> anyhow::Result<EngraveConfig> {
+        let settings = self.settings()?;
+        let chosen = settings
+            .iter()
+            .rev()
+            .find(|setting| setting.key == "preset")
+            .map(|setting| {
+                EngraveConfig::preset(setting.value)
+                    .ok_or_else(|| anyhow!("Unknown preset: {}", setting.value))
+            })
+            .transpose()?;
+        match chosen {
+            None if !settings.is_empty() => Err(anyhow!(
+                "Settings need a preset to choose the part they apply to"
+            )),
+            Some(chosen) if chosen == preset => preset.apply(&settings),
+            _ => Ok(preset),
+        }
+    }
+}
+
 fn main() -> anyhow::Result<()> {
+    let mut args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != EXPLAIN_FLAG)
+        .collect();
+    let options = Options::take(&mut args)?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] => solve(&options),
+        ["render", file, output] => run_render(file, output, false, &options),
+        ["render", file, output, "--shade"] => run_render(file, output, true, &options),
+        ["bench", file] => run_bench(file, &options),
+        ["savings", file] => run_savings(file, &options),
+        ["stats", file] => run_stats(file, false, &options),
+        ["stats", file, "--boundary"] => run_stats(file, true, &options),
+        ["orbit", file, rest @ ..] => run_orbit(file, rest, &options),
+        ["query", file, rest @ ..] => run_query(file, rest, &options),
+        _ => Err(anyhow!("{USAGE}")),
+    }
+}
+
+fn solve(options: &Options) -> anyhow::Result<()> {
+    let mut explain = Explain::from_args();
     let input = read_input("everybody_codes_e2025_q02_p1.txt")?;
-    let result = part1(&input)?;
+    let result = part1(
+        &input,
+        &options.part_config(EngraveConfig::PART1)?,
+        &mut explain,
+    )?;
     println!("{result}");
+    explain.print("part 1");
     let input = read_input("everybody_codes_e2025_q02_p2.txt")?;
-    let result = part2(&input)?;
+    let result = part2(
+        &input,
+        &options.part_config(EngraveConfig::PART2)?,
+        options.threads,
+        &mut explain,
+    )?;
     println!("{result}");
+    explain.print("part 2");
     let input = read_input("everybody_codes_e2025_q02_p3.txt")?;
-    let result = part3(&input)?;
+    let result = part3(
+        &input,
+        &options.part_config(EngraveConfig::PART3)?,
+        options.threads,
+        &mut explain,
+    )?;
     println!("{result}");
+    explain.print("part 3");
     Ok(())
 }
 
-fn part1(input: &str) -> anyhow::Result<Complex> {
-    let mut inp = input;
+/// Evaluates the engraving of `file` and writes it to `output` in the format
+/// given by its extension.
+fn run_render(file: &str, output: &str, shade: bool, options: &Options) -> anyhow::Result<()> {
+    let config = options.config(EngraveConfig::PART3)?;
+    let a = read_anchor(file, &config)?;
+    let grid = evaluate_grid(&a, &config, options.threads);
+    if output == "-" {
+        let mut out = io::stdout().lock();
+        write_image(&grid, Format::Ascii, shade, config.cycles, &mut out)?;
+        out.flush()?;
+    } else {
+        let format = Format::from_path(Path::new(output));
+        let mut out = BufWriter::new(File::create(output)?);
+        write_image(&grid, format, shade, config.cycles, &mut out)?;
+        // dropping the writer would discard errors writing its last buffer
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Times the evaluation of the grid of `file` on one thread and on
+/// `--threads` threads, then point by point and batched on one thread,
+/// checking that each pair gives the same results.
+fn run_bench(file: &str, options: &Options) -> anyhow::Result<()> {
+    let config = options.config(EngraveConfig::PART3)?;
+    let threads = options.threads;
+    let a = read_anchor(file, &config)?;
+    let start = Instant::now();
+    let sequential = evaluate_grid(&a, &config, 1);
+    let sequential_time = start.elapsed();
+    let start = Instant::now();
+    let parallel = evaluate_grid(&a, &config, threads);
+    let parallel_time = start.elapsed();
+    if parallel != sequential {
+        return Err(anyhow!(
+            "The grids evaluated on 1 and {threads} threads differ"
+        ));
+    }
+    println!("1 thread: {sequential_time:?}");
+    println!("{threads} threads: {parallel_time:?}");
+    println!(
+        "speedup: {:.2}x",
+        sequential_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+    let side = config.side();
+    let points: Vec<Complex> = Rect::new(
+        Point::new(0, 0),
+        Point::new(side as i64 - 1, side as i64 - 1),
+    )
+    .points()
+    .map(|p| sample(&a, &config, p))
+    .collect();
+    let start = Instant::now();
+    let scalar: Vec<bool> = points.iter().map(|p| should_plot(p, &config)).collect();
+    let scalar_time = start.elapsed();
+    let start = Instant::now();
+    let batched = plot_batch(&points, &config);
+    let batched_time = start.elapsed();
+    if batched != scalar {
+        return Err(anyhow!("The batched evaluation differs from should_plot"));
+    }
+    println!("should_plot: {scalar_time:?}");
+    println!("batched: {batched_time:?}");
+    println!(
+        "speedup: {:.2}x",
+        scalar_time.as_secs_f64() / batched_time.as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Prints the escape-time statistics of the grid of `file`.
+fn run_stats(file: &str, list_boundary: bool, options: &Options) -> anyhow::Result<()> {
+    let config = options.config(EngraveConfig::PART3)?;
+    let a = read_anchor(file, &config)?;
+    let stats = EscapeStats::new(&evaluate_grid(&a, &config, options.threads), config.cycles);
+    print!(
+        "{}",
+        stats.report(
+            |y| sample(&a, &config, Point::new(0, y as i64)).y,
+            |x| sample(&a, &config, Point::new(x as i64, 0)).x
+        )
+    );
+    if list_boundary {
+        for p in stats.boundary.iter() {
+            println!("{}", sample(&a, &config, *p));
+        }
+    }
+    Ok(())
+}
+
+/// Prints the trace of one point of the input of `file`.
+fn run_orbit(file: &str, args: &[&str], options: &Options) -> anyhow::Result<()> {
+    let (args, csv) = match args {
+        [rest @ .., "--csv"] => (rest, true),
+        _ => (args, false),
+    };
+    let (args, part1) = match args {
+        [rest @ .., "--part1"] => (rest, true),
+        _ => (args, false),
+    };
+    let offset = match args {
+        [] => Complex::new(0, 0),
+        [dx, dy] => Complex::new(dx.parse()?, dy.parse()?),
+        _ => return Err(anyhow!("{USAGE}")),
+    };
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
     let a = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let ten = Complex { x: 10, y: 10 };
-    let n = (0..3).fold(Complex { x: 0, y: 0 }, |r, _| {
-        let x = r.mul(&r);
-        let x = x.div(&ten);
-        x.add(&a)
-    });
-    Ok(n)
+    let p = a
+        .checked_add(offset)
+        .map_err(|e| anyhow!("The point {a} + {offset} is out of range: {e}"))?;
+    let orbit = if part1 {
+        Orbit::trace(p, &options.config(EngraveConfig::PART1)?, Check::Overflow)
+    } else {
+        Orbit::trace(p, &options.config(EngraveConfig::PART3)?, Check::Bounds)
+    };
+    print!("{}", if csv { orbit.csv() } else { orbit.table() });
+    Ok(())
 }
 
-fn part2(input: &str) -> anyhow::Result<usize> {
-    let mut inp = input;
+/// Answers the region queries `args` about the input of `file`, one after
+/// the other on the same cache.
+fn run_query(file: &str, args: &[&str], options: &Options) -> anyhow::Result<()> {
+    let (args, list) = match args {
+        [rest @ .., "--list"] => (rest, true),
+        _ => (args, false),
+    };
+    if args.is_empty() || args.len() % 5 != 0 {
+        return Err(anyhow!("{USAGE}"));
+    }
+    let config = options.config(EngraveConfig::PART3)?;
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
     let a = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let mut count = 0;
-    for y in 0..=100 {
-        for x in 0..=100 {
-            let z = Complex {
-                x: a.x + x * 10,
-                y: a.y + y * 10,
-            };
-            if should_plot(&z) {
-                count += 1;
+    let mut cache = RegionCache::new(config, options.threads);
+    for numbers in args.chunks(5) {
+        let numbers = numbers
+            .iter()
+            .map(|n| n.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let [x1, y1, x2, y2, step] = numbers[..] else {
+            unreachable!("chunks of 5 numbers")
+        };
+        let query = Query::new(a, Rect::new(Point::new(x1, y1), Point::new(x2, y2)), step)?;
+        let engraved = cache.list(&query);
+        let (evaluated, cached) = cache.counters();
+        println!(
+            "{x1},{y1} to {x2},{y2} step {step}: {} engraved (evaluated {evaluated}, cached {cached} so far)",
+            engraved.len()
+        );
+        if list {
+            for p in engraved.iter() {
+                println!("{}", Complex::new(p.x, p.y));
             }
         }
-        // println!();
     }
-    Ok(count)
+    Ok(())
+}
+
+/// Prints how many cycles detecting repeated values saved on the grid of
+/// `file`.
+fn run_savings(file: &str, options: &Options) -> anyhow::Result<()> {
+    let config = options.config(EngraveConfig::PART3)?;
+    let a = read_anchor(file, &config)?;
+    let savings = Savings::of_grid(&a, &config, options.threads);
+    let total = savings.computed + savings.saved;
+    println!("points: {}", savings.points);
+    println!("cycles computed: {}", savings.computed);
+    println!(
+        "cycles saved: {} ({:.1}%)",
+        savings.saved,
+        100.0 * savings.saved as f64 / total.max(1) as f64
+    );
+    Ok(())
 }
 
-fn part3(input: &str) -> anyhow::Result<usize> {
+fn part1(input: &str, config: &EngraveConfig, explain: &mut Explain) -> anyhow::Result<Complex> {
     let mut inp = input;
     let a = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let mut count = 0;
-    for y in 0..=1000 {
-        for x in 0..=1000 {
-            let z = Complex {
-                x: a.x + x,
-                y: a.y + y,
-            };
-            if should_plot(&z) {
-                count += 1;
-            }
-        }
+    // the square is taken before dividing, so work in i128 and only require
+    // the final result to fit
+    let a = Complex::<i128>::from(a);
+    let divisor = Complex::<i128>::from(config.divisor);
+    let mut r = Complex::new(0, 0);
+    for cycle in 1..=config.cycles {
+        r = r.checked_mul(r)?.checked_div(divisor)?.checked_add(a)?;
+        explain.step(|| format!("cycle {cycle}: {r}"));
     }
-    Ok(count)
+    Ok(Complex::try_from(r)?)
 }
 
-fn should_plot(p: &Complex) -> bool {
-    let divisor = Complex {
-        x: 100000,
-        y: 100000,
-    };
-    let mut r = Complex { x: 0, y: 0 };
-    for _i in 1..=100 {
-        r = r.mul(&r);
-        r = r.div(&divisor);
-        r = r.add(p);
-        if !(-1_000_000..=1_000_000).contains(&r.x) || !(-1_000_000..=1_000_000).contains(&r.y) {
-            // println!("p={p} r={r} c={i}");
-            return false;
-        }
+fn part2(
+    input: &str,
+    config: &EngraveConfig,
+    threads: usize,
+    explain: &mut Explain,
+) -> anyhow::Result<usize> {
+    let mut inp = input;
+    let a = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    count_engraved(a, config, threads, explain)
+}
+
+fn part3(
+    input: &str,
+    config: &EngraveConfig,
+    threads: usize,
+    explain: &mut Explain,
+) -> anyhow::Result<usize> {
+    let mut inp = input;
+    let a = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    count_engraved(a, config, threads, explain)
+}
+
+/// Counts the engraved points of the grid of `config` anchored at `a`,
+/// tracing the number of engraved points of each row.
+fn count_engraved(
+    a: Complex,
+    config: &EngraveConfig,
+    threads: usize,
+    explain: &mut Explain,
+) -> anyhow::Result<usize> {
+    let mut cache = RegionCache::new(*config, threads);
+    let query = Query::preset(a, config)?;
+    if !explain.is_enabled() {
+        return Ok(cache.count(&query));
+    }
+    let engraved = cache.list(&query);
+    let mut rows: BTreeMap<i64, usize> = BTreeMap::new();
+    for p in engraved.iter() {
+        *rows.entry(p.y).or_insert(0) += 1;
     }
-    true
+    for (y, count) in rows {
+        explain.step(|| format!("y = {y}: {count} engraved"));
+    }
+    Ok(engraved.len())
 }
 
-fn read_input(path: impl AsRef<Path>) -> anyhow::Result<String> {
-    let result = std::fs::read_to_string(path)?;
-    Ok(result)
+/// Reads the point A of `file` and checks that the grid of `config` anchored
+/// at it stays in range.
+fn read_anchor(file: &str, config: &EngraveConfig) -> anyhow::Result<Complex> {
+    let input = read_input(file)?;
+    let mut inp = input.as_str();
+    let a = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
+    config.check_anchor(&a)?;
+    Ok(a)
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Complex {
-    x: i64,
-    y: i64,
+/// Evaluates the grid anchored at `a` on `threads` threads. Each cell holds
+/// the result of [`escape_cycle`].
+fn evaluate_grid(a: &Complex, config: &EngraveConfig, threads: usize) -> Grid<Option<u32>> {
+    let side = config.side();
+    evaluate_rows(side, side, threads, |p| {
+        escape_cycle(&sample(a, config, p), config)
+    })
 }
 
-impl Complex {
-    fn add(&self, other: &Complex) -> Complex {
-        Complex {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
-    }
+/// The point at grid position `p` of the grid anchored at `a`. Positions
+/// within [`EngraveConfig::side`] lie at most `size` from `a`, which
+/// [`EngraveConfig::check_anchor`] keeps in range.
+fn sample(a: &Complex, config: &EngraveConfig, p: Point) -> Complex {
+    Complex::new(a.x + p.x * config.step, a.y + p.y * config.step)
+}
 
-    fn mul(&self, other: &Complex) -> Complex {
-        Complex {
-            x: self.x * other.x - self.y * other.y,
-            y: self.x * other.y + self.y * other.x,
-        }
-    }
+fn should_plot(p: &Complex, config: &EngraveConfig) -> bool {
+    escape_cycle(p, config).is_none()
+}
+
+/// The cycle (counting from 1) in which the engraving of `p` escapes the
+/// bounds, or `None` if it stays inside and `p` is engraved.
+fn escape_cycle(p: &Complex, config: &EngraveConfig) -> Option<u32> {
+    iterate(p, config).escape
+}
 
-    fn div(&self, other: &Complex) -> Complex {
-        Complex {
-            x: self.x / other.x,
-            y: self.y / other.y,
+/// How much work detecting repeated values saves over a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Savings {
+    points: usize,
+    /// Cycles computed in total.
+    computed: u64,
+    /// Cycles engraved points skipped after their values started repeating.
+    saved: u64,
+}
+
+impl Savings {
+    fn of_grid(a: &Complex, config: &EngraveConfig, threads: usize) -> Self {
+        let side = config.side();
+        let grid = evaluate_rows(side, side, threads, |p| {
+            iterate(&sample(a, config, p), config)
+        });
+        let mut savings = Savings {
+            points: 0,
+            computed: 0,
+            saved: 0,
+        };
+        for (_, iteration) in grid.iter() {
+            savings.points += 1;
+            savings.computed += u64::from(iteration.computed);
+            if iteration.escape.is_none() {
+                savings.saved += u64::from(config.cycles - iteration.computed);
+            }
         }
+        savings
     }
 }
 
-impl fmt::Display for Complex {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[{},{}]", self.x, self.y)
+/// Outcome of running the cycles for one point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Iteration {
+    /// See [`escape_cycle`].
+    escape: Option<u32>,
+    /// Number of cycles actually computed.
+    computed: u32,
+}
+
+/// Runs the cycles for `p`, stopping as soon as the result escapes or
+/// repeats an earlier value.
+///
+/// The integer arithmetic often settles into a fixed point or a short loop.
+/// Once a value repeats, every later value has been seen and checked
+/// already, so the point can never escape. Repeats are found with Brent's
+/// algorithm, which only keeps one earlier value around.
+fn iterate(p: &Complex, config: &EngraveConfig) -> Iteration {
+    let mut r = Complex::new(0, 0);
+    let mut saved = r;
+    let mut power = 1;
+    let mut length = 0;
+    for i in 1..=config.cycles {
+        // the components stay within the validated bound, so this cannot overflow
+        r = r * r / config.divisor + *p;
+        if config.escaped(&r) {
+            return Iteration {
+                escape: Some(i),
+                computed: i,
+            };
+        }
+        if r == saved {
+            return Iteration {
+                escape: None,
+                computed: i,
+            };
+        }
+        length += 1;
+        if length == power {
+            saved = r;
+            power *= 2;
+            length = 0;
+        }
+    }
+    Iteration {
+        escape: None,
+        computed: config.cycles,
     }
 }
 
@@ -148,32 +568,134 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(unused_mut)]
     fn test_part1() {
         let mut input = "A=[25,9]";
-        let result = part1(input).expect("should work");
+        let result =
+            part1(input, &EngraveConfig::PART1, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, Complex { x: 357, y: 862 });
     }
 
     #[test]
     fn test_part2() {
         let input = "A=[35300,-64910]";
-        let result = part2(input).expect("should work");
+        let result =
+            part2(input, &EngraveConfig::PART2, 1, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, 4076);
     }
 
+    #[test]
+    fn test_explained() {
+        let mut explain = Explain::new(true);
+        part1("A=[25,9]", &EngraveConfig::PART1, &mut explain).unwrap();
+        assert_eq!(
+            explain.lines(),
+            ["cycle 1: [25,9]", "cycle 2: [79,54]", "cycle 3: [357,862]"]
+        );
+        let mut explain = Explain::new(true);
+        let count = part2("A=[35300,-64910]", &EngraveConfig::PART2, 1, &mut explain).unwrap();
+        let traced: usize = explain
+            .lines()
+            .iter()
+            .map(|line| {
+                line.rsplit(": ")
+                    .next()
+                    .unwrap()
+                    .trim_end_matches(" engraved")
+            })
+            .map(|count| count.parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(explain.lines()[0], "y = -64880: 1 engraved");
+        assert_eq!((count, traced), (4076, 4076));
+    }
+
     #[test]
     fn test_part3() {
         let input = "A=[35300,-64910]";
-        let result = part3(input).expect("should work");
+        let result =
+            part3(input, &EngraveConfig::PART3, 4, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, 406954);
     }
 
     #[test]
+    fn test_evaluate_grid() {
+        let a = Complex::new(35300, -64910);
+        let grid = evaluate_grid(&a, &EngraveConfig::PART2, 1);
+        assert_eq!((grid.width(), grid.height()), (101, 101));
+        assert_eq!(grid.iter().filter(|(_, c)| c.is_none()).count(), 4076);
+        // [35460,-64910] escapes, see below
+        assert!(grid.get(&Point::new(16, 0)).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_escape_stats() {
+        let a = Complex::new(35300, -64910);
+        let config = EngraveConfig::PART2;
+        let map = evaluate_grid(&a, &config, 1);
+        let stats = EscapeStats::new(&map, config.cycles);
+        assert_eq!(stats.engraved, 4076);
+        assert_eq!(stats.histogram.iter().sum::<usize>(), 101 * 101 - 4076);
+        assert!(stats.boundary.iter().all(|p| map.get(p) == Some(&None)));
+        // [35460,-64910] is on the first row
+        let p = Point::new(16, 0);
+        assert_eq!(sample(&a, &config, p), Complex::new(35460, -64910));
+        let cycle = map.get(&p).unwrap().expect("escapes");
+        assert!(stats.rows[0].first_escape <= Some(cycle));
+        assert!(stats.rows[0].last_escape >= Some(cycle));
+    }
+
+    /// The plain loop, without detecting repeats.
+    fn naive_escape_cycle(p: &Complex, config: &EngraveConfig) -> Option<u32> {
+        let mut r = Complex::new(0, 0);
+        for i in 1..=config.cycles {
+            r = r * r / config.divisor + *p;
+            if config.escaped(&r) {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_repeat_detection_is_exact() {
+        let a = Complex::new(35300, -64910);
+        let patch = EngraveConfig {
+            size: 250,
+            ..EngraveConfig::PART3
+        };
+        for config in [EngraveConfig::PART2, patch] {
+            let side = config.side();
+            let fast = evaluate_grid(&a, &config, 2);
+            let naive = Grid::from_fn(side, side, |p| {
+                naive_escape_cycle(&sample(&a, &config, p), &config)
+            });
+            assert_eq!(fast, naive);
+        }
+        let savings = Savings::of_grid(&a, &EngraveConfig::PART2, 1);
+        assert_eq!(savings.points, 101 * 101);
+        assert!(savings.saved > 0);
+        assert_eq!(
+            savings.computed + savings.saved,
+            (0..101 * 101)
+                .map(|i| Point::new(i % 101, i / 101))
+                .map(|p| {
+                    match escape_cycle(&sample(&a, &EngraveConfig::PART2, p), &EngraveConfig::PART2)
+                    {
+                        Some(cycle) => u64::from(cycle),
+                        None => 100,
+                    }
+                })
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::bool_comparison)]
     fn test_should_plot() {
         let p = Complex {
             x: 35460,
             y: -64910,
         };
-        assert!(should_plot(&p) == false);
+        assert!(should_plot(&p, &EngraveConfig::PART3) == false);
     }
 }
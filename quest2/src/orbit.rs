@@ -0,0 +1,236 @@
+//! Step by step traces of the cycles run for a single point.
+
+use crate::complex::{ArithmeticError, Complex};
+use crate::config::EngraveConfig;
+use std::fmt::{self, Write};
+
+/// Which check a trace applies after each cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Part 1: only fail if the arithmetic overflows `i128`.
+    Overflow,
+    /// Parts 2 and 3: the point escapes once the result leaves the bounds.
+    Bounds,
+}
+
+/// Why an orbit stopped before running all cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// The named component of the result left `-bound..=bound`.
+    Bounds(char),
+    Arithmetic(ArithmeticError),
+}
+
+impl fmt::Display for Escape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Escape::Bounds(component) => write!(f, "{component} out of bounds"),
+            Escape::Arithmetic(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// The values of one cycle `r = r * r / divisor + p`. Values the cycle did
+/// not get to because the arithmetic failed are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub cycle: u32,
+    pub product: Option<Complex<i128>>,
+    pub quotient: Option<Complex<i128>>,
+    pub sum: Option<Complex<i128>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orbit {
+    pub steps: Vec<Step>,
+    /// Set if the last step escaped.
+    pub escape: Option<Escape>,
+}
+
+impl Orbit {
+    /// Runs the cycles of `config` for `p`, stopping at the first step that
+    /// fails `check`.
+    ///
+    /// Unlike [`should_plot`](crate::should_plot) this does not stop when
+    /// values repeat, so the trace always covers every cycle up to an escape.
+    pub fn trace(p: Complex, config: &EngraveConfig, check: Check) -> Self {
+        let p = Complex::<i128>::from(p);
+        let divisor = Complex::<i128>::from(config.divisor);
+        let bound = i128::from(config.bound);
+        let mut orbit = Orbit {
+            steps: Vec::new(),
+            escape: None,
+        };
+        let mut r = Complex::new(0, 0);
+        for cycle in 1..=config.cycles {
+            let mut step = Step {
+                cycle,
+                product: None,
+                quotient: None,
+                sum: None,
+            };
+            let result = r.checked_mul(r).and_then(|product| {
+                step.product = Some(product);
+                let quotient = product.checked_div(divisor)?;
+                step.quotient = Some(quotient);
+                let sum = quotient.checked_add(p)?;
+                step.sum = Some(sum);
+                Ok(sum)
+            });
+            orbit.steps.push(step);
+            r = match result {
+                Ok(sum) => sum,
+                Err(e) => {
+                    orbit.escape = Some(Escape::Arithmetic(e));
+                    break;
+                }
+            };
+            if check == Check::Bounds {
+                let bounds = -bound..=bound;
+                let component = [('x', r.x), ('y', r.y)]
+                    .into_iter()
+                    .find(|(_, value)| !bounds.contains(value));
+                if let Some((name, _)) = component {
+                    orbit.escape = Some(Escape::Bounds(name));
+                    break;
+                }
+            }
+        }
+        orbit
+    }
+
+    /// An aligned table with one row per cycle.
+    pub fn table(&self) -> String {
+        let rows = self.rows();
+        let mut widths = [0; 5];
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        let mut out = String::new();
+        for row in rows.iter() {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:>width$}"))
+                .collect();
+            writeln!(out, "{}", cells.join("  ").trim_end()).unwrap();
+        }
+        out
+    }
+
+    /// The same rows as [`Orbit::table`] as CSV, with the components of each
+    /// value in their own columns.
+    pub fn csv(&self) -> String {
+        let mut out =
+            String::from("cycle,product_x,product_y,quotient_x,quotient_y,sum_x,sum_y,escape\n");
+        let component = |value: Option<Complex<i128>>| {
+            value.map_or(",".to_string(), |c| format!("{},{}", c.x, c.y))
+        };
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                step.cycle,
+                component(step.product),
+                component(step.quotient),
+                component(step.sum),
+                self.escape_note(i)
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    fn rows(&self) -> Vec<[String; 5]> {
+        let value = |value: Option<Complex<i128>>| value.map_or("-".to_string(), |c| c.to_string());
+        let header = ["cycle", "r * r", "/ divisor", "+ p", "escape"].map(String::from);
+        let steps = self.steps.iter().enumerate().map(|(i, step)| {
+            [
+                step.cycle.to_string(),
+                value(step.product),
+                value(step.quotient),
+                value(step.sum),
+                self.escape_note(i),
+            ]
+        });
+        std::iter::once(header).chain(steps).collect()
+    }
+
+    /// The reason for escaping on the row of the escaping step, empty on
+    /// the others.
+    fn escape_note(&self, i: usize) -> String {
+        match self.escape {
+            Some(escape) if i + 1 == self.steps.len() => escape.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let orbit = Orbit::trace(Complex::new(25, 9), &EngraveConfig::PART1, Check::Overflow);
+        assert_eq!(orbit.escape, None);
+        assert_eq!(orbit.steps[2].sum, Some(Complex::new(357, 862)));
+        assert_eq!(
+            orbit.steps[1],
+            Step {
+                cycle: 2,
+                product: Some(Complex::new(544, 450)),
+                quotient: Some(Complex::new(54, 45)),
+                sum: Some(Complex::new(79, 54)),
+            }
+        );
+        assert_eq!(
+            orbit.table(),
+            "\
+cycle        r * r  / divisor        + p  escape
+    1        [0,0]      [0,0]     [25,9]
+    2    [544,450]    [54,45]    [79,54]
+    3  [3325,8532]  [332,853]  [357,862]
+"
+        );
+    }
+
+    #[test]
+    fn test_escape_is_marked() {
+        let p = Complex::new(35400, -64810);
+        let orbit = Orbit::trace(p, &EngraveConfig::PART2, Check::Bounds);
+        assert_eq!(orbit.steps.len(), 30);
+        assert_eq!(orbit.escape, Some(Escape::Bounds('x')));
+        let csv = orbit.csv();
+        assert!(csv.starts_with("cycle,product_x,"));
+        assert!(csv.lines().last().unwrap().ends_with("out of bounds"));
+        assert_eq!(csv.lines().filter(|l| l.ends_with(',')).count(), 29);
+        // an engraved point runs every cycle
+        let p = Complex::new(35630, -64880);
+        let orbit = Orbit::trace(p, &EngraveConfig::PART2, Check::Bounds);
+        assert!(crate::should_plot(&p, &EngraveConfig::PART2));
+        assert_eq!(orbit.escape, None);
+        assert_eq!(orbit.steps.len(), 100);
+    }
+
+    #[test]
+    fn test_overflow_is_marked() {
+        let config = EngraveConfig {
+            cycles: 10,
+            divisor: Complex::new(1, 1),
+            ..EngraveConfig::PART1
+        };
+        let orbit = Orbit::trace(Complex::new(1_000_000, 1), &config, Check::Overflow);
+        let last = orbit.steps.last().unwrap();
+        assert_eq!(
+            orbit.escape,
+            Some(Escape::Arithmetic(ArithmeticError::Overflow(
+                "multiplication"
+            )))
+        );
+        assert_eq!(last.product, None);
+        assert!(orbit.table().ends_with("-  overflow in multiplication\n"));
+    }
+}
@@ -0,0 +1,109 @@
+use common::geometry::Point;
+use common::grid::Grid;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Number of threads to use when none is given: one per core.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Builds a `width` x `height` grid by calling `f` for every cell, spread
+/// over `threads` scoped threads.
+///
+/// Threads take whole rows from a shared counter, so rows that are slow to
+/// evaluate do not hold up the others. Every row is stored at its own index,
+/// which makes the result identical to [`Grid::from_fn`] whatever the number
+/// of threads.
+pub fn evaluate_rows<T: Send>(
+    width: usize,
+    height: usize,
+    threads: usize,
+    f: impl Fn(Point) -> T + Sync,
+) -> Grid<T> {
+    let threads = threads.clamp(1, height.max(1));
+    if threads == 1 {
+        return Grid::from_fn(width, height, f);
+    }
+    let next_row = AtomicUsize::new(0);
+    let mut rows: Vec<(usize, Vec<T>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let y = next_row.fetch_add(1, Ordering::Relaxed);
+                        if y >= height {
+                            return done;
+                        }
+                        let row = (0..width)
+                            .map(|x| f(Point::new(x as i64, y as i64)))
+                            .collect();
+                        done.push((y, row));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("worker thread panicked"))
+            .collect()
+    });
+    rows.sort_unstable_by_key(|(y, _)| *y);
+    let cells = rows.into_iter().flat_map(|(_, row)| row).collect();
+    Grid::from_cells(width, height, cells).expect("every row is evaluated once")
+}
+
+/// Calls `f` for every item on `threads` scoped threads, each taking one
+/// contiguous chunk, and returns the results in the order of `items`.
+pub fn map_slice<I: Sync, T: Send>(
+    items: &[I],
+    threads: usize,
+    f: impl Fn(&I) -> T + Sync,
+) -> Vec<T> {
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.iter().map(f).collect();
+    }
+    let chunk = items.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let workers: Vec<_> = items
+            .chunks(chunk)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<T>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_sequential() {
+        let f = |p: Point| p.x * 31 + p.y * p.y;
+        let expected = Grid::from_fn(17, 23, f);
+        for threads in [0, 1, 2, 5, 64] {
+            assert_eq!(evaluate_rows(17, 23, threads, f), expected, "{threads}");
+        }
+    }
+
+    #[test]
+    fn test_map_slice_keeps_order() {
+        let items: Vec<i64> = (0..1000).collect();
+        for threads in [1, 3, 7] {
+            let result = map_slice(&items, threads, |i| i * i);
+            assert_eq!(result, items.iter().map(|i| i * i).collect::<Vec<_>>());
+        }
+        assert!(map_slice(&[] as &[i64], 4, |i| *i).is_empty());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(evaluate_rows(0, 0, 4, |_| 0).width(), 0);
+        assert_eq!(evaluate_rows(3, 0, 4, |_| 0).height(), 0);
+    }
+}
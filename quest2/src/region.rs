@@ -0,0 +1,222 @@
+use crate::complex::Complex;
+use crate::config::EngraveConfig;
+use crate::parallel::map_slice;
+use crate::should_plot;
+use anyhow::bail;
+use common::geometry::{Point, Rect};
+use std::collections::HashMap;
+
+/// Side length of the square tiles the cache is made of.
+const TILE: i64 = 64;
+
+/// States of a cached point.
+const UNKNOWN: u8 = 0;
+const ENGRAVED: u8 = 1;
+const ESCAPED: u8 = 2;
+
+/// Most points a single query may sample, which bounds the time and memory
+/// one query can take.
+const MAX_QUERY_POINTS: i128 = 10_000_000;
+
+/// A rectangle of points, sampled every `step`-th point starting at its top
+/// left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Query {
+    /// Absolute corners, both included.
+    area: Rect,
+    step: i64,
+}
+
+impl Query {
+    /// The points at offsets `area` from `anchor`, sampled every `step`-th
+    /// point. Fails unless every point fits in an `i64` and the query samples
+    /// at most [`MAX_QUERY_POINTS`] points.
+    pub fn new(anchor: Complex, area: Rect, step: i64) -> anyhow::Result<Self> {
+        if step <= 0 {
+            bail!("The step must be positive");
+        }
+        let shift = |p: Point| {
+            Some(Point::new(
+                anchor.x.checked_add(p.x)?,
+                anchor.y.checked_add(p.y)?,
+            ))
+        };
+        let (Some(min), Some(max)) = (shift(area.min), shift(area.max)) else {
+            bail!(
+                "The query from {},{} to {},{} of {anchor} is out of range",
+                area.min.x,
+                area.min.y,
+                area.max.x,
+                area.max.y
+            );
+        };
+        let samples =
+            |min: i64, max: i64| (i128::from(max) - i128::from(min)) / i128::from(step) + 1;
+        let points = samples(min.x, max.x).checked_mul(samples(min.y, max.y));
+        if points.is_none_or(|points| points > MAX_QUERY_POINTS) {
+            bail!("The query samples more than {MAX_QUERY_POINTS} points");
+        }
+        Ok(Query {
+            area: Rect::new(min, max),
+            step,
+        })
+    }
+
+    /// The square that parts 2 and 3 ask about: offsets `0..=size` from `a`
+    /// sampled every `step`-th point.
+    pub fn preset(a: Complex, config: &EngraveConfig) -> anyhow::Result<Self> {
+        let area = Rect::new(Point::new(0, 0), Point::new(config.size, config.size));
+        Self::new(a, area, config.step)
+    }
+
+    /// The points of the query, row by row.
+    fn points(&self) -> impl Iterator<Item = Point> + use<> {
+        self.area.points_step(self.step)
+    }
+}
+
+/// Answers region queries, remembering every point it evaluated so that
+/// overlapping queries only evaluate the points they have not seen yet.
+///
+/// Points are cached by their absolute coordinates in square tiles, so
+/// queries with different anchors and steps share the cache as well.
+pub struct RegionCache {
+    config: EngraveConfig,
+    threads: usize,
+    tiles: HashMap<(i64, i64), Vec<u8>>,
+    evaluated: u64,
+    cached: u64,
+}
+
+impl RegionCache {
+    /// Only the fields of `config` that decide whether a point is engraved
+    /// are used; the queries give the area and step.
+    pub fn new(config: EngraveConfig, threads: usize) -> Self {
+        RegionCache {
+            config,
+            threads,
+            tiles: HashMap::new(),
+            evaluated: 0,
+            cached: 0,
+        }
+    }
+
+    pub fn count(&mut self, query: &Query) -> usize {
+        self.evaluate(query)
+            .into_iter()
+            .filter(|(_, engraved)| *engraved)
+            .count()
+    }
+
+    /// The engraved points of the query in absolute coordinates, row by row.
+    pub fn list(&mut self, query: &Query) -> Vec<Point> {
+        self.evaluate(query)
+            .into_iter()
+            .filter(|(_, engraved)| *engraved)
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    /// Number of points evaluated so far and number of lookups answered from
+    /// the cache.
+    pub fn counters(&self) -> (u64, u64) {
+        (self.evaluated, self.cached)
+    }
+
+    fn evaluate(&mut self, query: &Query) -> Vec<(Point, bool)> {
+        let points: Vec<Point> = query.points().collect();
+        let missing: Vec<Point> = points
+            .iter()
+            .filter(|p| self.state(p) == UNKNOWN)
+            .copied()
+            .collect();
+        let config = self.config;
+        let results = map_slice(&missing, self.threads, |p| {
+            should_plot(&Complex::new(p.x, p.y), &config)
+        });
+        for (p, engraved) in missing.iter().zip(results) {
+            let (key, index) = tile_position(p);
+            let tile = self
+                .tiles
+                .entry(key)
+                .or_insert_with(|| vec![UNKNOWN; (TILE * TILE) as usize]);
+            tile[index] = if engraved { ENGRAVED } else { ESCAPED };
+        }
+        self.evaluated += missing.len() as u64;
+        self.cached += (points.len() - missing.len()) as u64;
+        points
+            .into_iter()
+            .map(|p| (p, self.state(&p) == ENGRAVED))
+            .collect()
+    }
+
+    fn state(&self, p: &Point) -> u8 {
+        let (key, index) = tile_position(p);
+        self.tiles.get(&key).map_or(UNKNOWN, |tile| tile[index])
+    }
+}
+
+/// The tile containing `p` and the index of `p` within it.
+fn tile_position(p: &Point) -> ((i64, i64), usize) {
+    let key = (p.x.div_euclid(TILE), p.y.div_euclid(TILE));
+    let index = p.y.rem_euclid(TILE) * TILE + p.x.rem_euclid(TILE);
+    (key, index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Complex = Complex {
+        x: 35300,
+        y: -64910,
+    };
+
+    #[test]
+    fn test_presets_match_parts() {
+        let mut cache = RegionCache::new(EngraveConfig::PART2, 2);
+        let query = Query::preset(A, &EngraveConfig::PART2).unwrap();
+        assert_eq!(cache.count(&query), 4076);
+        assert_eq!(cache.counters(), (101 * 101, 0));
+        // the same query again is answered from the cache
+        assert_eq!(cache.list(&query).len(), 4076);
+        assert_eq!(cache.counters(), (101 * 101, 101 * 101));
+    }
+
+    #[test]
+    fn test_zooming_in_reuses_points() {
+        let mut cache = RegionCache::new(EngraveConfig::PART3, 1);
+        let coarse = Query::new(A, Rect::new(Point::new(0, 0), Point::new(100, 100)), 10).unwrap();
+        cache.count(&coarse);
+        // a finer query over part of the same area, written relative to a
+        // different anchor
+        let anchor = Complex::new(A.x + 50, A.y + 50);
+        let fine =
+            Query::new(anchor, Rect::new(Point::new(-50, -50), Point::new(0, 0)), 5).unwrap();
+        let engraved = cache.list(&fine);
+        assert_eq!(cache.counters(), (121 + 11 * 11 - 36, 36));
+        for p in engraved.iter() {
+            assert!(should_plot(&Complex::new(p.x, p.y), &EngraveConfig::PART3));
+        }
+        assert_eq!(engraved.len(), cache.count(&fine));
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        let area = |x1, y1, x2, y2| Rect::new(Point::new(x1, y1), Point::new(x2, y2));
+        assert!(Query::new(A, area(0, 0, 10, 10), 0).is_err());
+        let error = Query::new(A, area(0, 0, i64::MAX, 0), 1).unwrap_err();
+        assert!(error.to_string().ends_with("is out of range"));
+        let huge = area(i64::MIN + 100_000, 0, i64::MAX - 100_000, 0);
+        assert!(Query::new(A, huge, 1).is_err());
+        assert!(Query::new(A, area(0, 0, 9999, 999), 1).is_ok());
+        assert!(Query::new(A, area(0, 0, 9999, 1000), 1).is_err());
+        assert!(Query::new(A, area(0, 0, 99990, 9999), 10).is_ok());
+    }
+
+    #[test]
+    fn test_tile_position() {
+        assert_eq!(tile_position(&Point::new(0, 0)), ((0, 0), 0));
+        assert_eq!(tile_position(&Point::new(-1, 65)), ((-1, 1), 127));
+    }
+}
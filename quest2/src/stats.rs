@@ -0,0 +1,179 @@
+use common::geometry::Point;
+use common::grid::Grid;
+use std::fmt::Write;
+
+/// Summary of one row or column of an escape-time map.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineSummary {
+    pub engraved: usize,
+    pub boundary: usize,
+    /// Earliest and latest cycle in which a point of the line escaped.
+    pub first_escape: Option<u32>,
+    pub last_escape: Option<u32>,
+}
+
+impl LineSummary {
+    fn add(&mut self, cell: Option<u32>, boundary: bool) {
+        match cell {
+            None => self.engraved += 1,
+            Some(cycle) => {
+                self.first_escape = Some(self.first_escape.map_or(cycle, |c| c.min(cycle)));
+                self.last_escape = Some(self.last_escape.map_or(cycle, |c| c.max(cycle)));
+            }
+        }
+        if boundary {
+            self.boundary += 1;
+        }
+    }
+}
+
+/// Statistics of an escape-time map, where each cell holds the cycle in
+/// which the point escaped or `None` if it is engraved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscapeStats {
+    /// `histogram[i]` is the number of points escaping in cycle `i + 1`.
+    pub histogram: Vec<usize>,
+    pub engraved: usize,
+    /// Engraved points next to a point that escaped, in grid coordinates.
+    pub boundary: Vec<Point>,
+    pub rows: Vec<LineSummary>,
+    pub columns: Vec<LineSummary>,
+}
+
+impl EscapeStats {
+    pub fn new(map: &Grid<Option<u32>>, cycles: u32) -> Self {
+        let mut stats = EscapeStats {
+            histogram: vec![0; cycles as usize],
+            engraved: 0,
+            boundary: Vec::new(),
+            rows: vec![LineSummary::default(); map.height()],
+            columns: vec![LineSummary::default(); map.width()],
+        };
+        for (p, cell) in map.iter() {
+            let boundary = cell.is_none()
+                && map
+                    .neighbors4(&p)
+                    .any(|n| map.get(&n).is_some_and(Option::is_some));
+            match cell {
+                None => stats.engraved += 1,
+                Some(cycle) => stats.histogram[*cycle as usize - 1] += 1,
+            }
+            if boundary {
+                stats.boundary.push(p);
+            }
+            stats.rows[p.y as usize].add(*cell, boundary);
+            stats.columns[p.x as usize].add(*cell, boundary);
+        }
+        stats
+    }
+
+    /// A human readable report. `label_row` and `label_column` turn grid
+    /// indices into the coordinates shown.
+    pub fn report(
+        &self,
+        label_row: impl Fn(usize) -> i64,
+        label_column: impl Fn(usize) -> i64,
+    ) -> String {
+        let total = self.engraved + self.histogram.iter().sum::<usize>();
+        let widest = self.histogram.iter().copied().max().unwrap_or(0).max(1);
+        let mut out = String::new();
+        writeln!(out, "engraved: {} of {total}", self.engraved).unwrap();
+        writeln!(out, "boundary points: {}", self.boundary.len()).unwrap();
+        writeln!(out, "escaped in cycle:").unwrap();
+        for (i, count) in self.histogram.iter().enumerate() {
+            if *count > 0 {
+                let bar = "#".repeat((count * 50).div_ceil(widest));
+                writeln!(out, "{:>5} {count:>8} {bar}", i + 1).unwrap();
+            }
+        }
+        for (title, lines, label) in [
+            ("row", &self.rows, &label_row as &dyn Fn(usize) -> i64),
+            ("column", &self.columns, &label_column),
+        ] {
+            writeln!(
+                out,
+                "{title:>8} {:>8} {:>8} {:>6} {:>6}",
+                "engraved", "boundary", "first", "last"
+            )
+            .unwrap();
+            for (i, line) in lines.iter().enumerate() {
+                let cycle = |c: Option<u32>| c.map_or("-".to_string(), |c| c.to_string());
+                writeln!(
+                    out,
+                    "{:>8} {:>8} {:>8} {:>6} {:>6}",
+                    label(i),
+                    line.engraved,
+                    line.boundary,
+                    cycle(line.first_escape),
+                    cycle(line.last_escape)
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // . escapes in cycle 1, digits in that cycle, X is engraved
+    const MAP: &str = "\
+        .3X\n\
+        XXX\n\
+        .XX\n";
+
+    fn map() -> Grid<Option<u32>> {
+        Grid::parse(MAP, |c| {
+            Ok(match c {
+                'X' => None,
+                '.' => Some(1),
+                c => Some(c.to_digit(10).unwrap()),
+            })
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_histogram_and_boundary() {
+        let stats = EscapeStats::new(&map(), 4);
+        assert_eq!(stats.histogram, [2, 0, 1, 0]);
+        assert_eq!(stats.engraved, 6);
+        assert_eq!(
+            stats.boundary,
+            [
+                Point::new(2, 0),
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(1, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_summaries() {
+        let stats = EscapeStats::new(&map(), 4);
+        assert_eq!(
+            stats.rows[0],
+            LineSummary {
+                engraved: 1,
+                boundary: 1,
+                first_escape: Some(1),
+                last_escape: Some(3),
+            }
+        );
+        assert_eq!(
+            stats.columns[2],
+            LineSummary {
+                engraved: 3,
+                boundary: 1,
+                ..Default::default()
+            }
+        );
+        let report = stats.report(|y| y as i64 * 10, |x| x as i64);
+        assert!(report.starts_with("engraved: 6 of 9\nboundary points: 4\n"));
+        assert!(report.contains("\n    3        1 #"));
+        assert!(report.contains("\n      20        2        1      1      1\n"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Crates with three dimensions, written `WxHxD`.
+//!
+//! A crate nests in another only if it is strictly smaller in every
+//! dimension, which orders the crates only partially: `2x5x5` and `5x2x5`
+//! fit neither way.
+
+use std::collections::VecDeque;
+use std::fmt;
+use winnow::ModalResult;
+use winnow::Parser;
+use winnow::ascii::{digit1, multispace0, multispace1};
+use winnow::combinator::{alt, eof, separated, terminated};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Crate {
+    pub width: i32,
+    pub height: i32,
+    pub depth: i32,
+}
+
+impl Crate {
+    pub fn new(width: i32, height: i32, depth: i32) -> Self {
+        Crate {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// Whether `self` nests inside `other`.
+    pub fn fits_in(&self, other: &Crate) -> bool {
+        self.width < other.width && self.height < other.height && self.depth < other.depth
+    }
+}
+
+impl fmt::Display for Crate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}x{}", self.width, self.height, self.depth)
+    }
+}
+
+/// Crates separated by commas or whitespace.
+pub fn parse_crates(input: &mut &str) -> ModalResult<Vec<Crate>> {
+    terminated(
+        separated(
+            1..,
+            parse_crate,
+            alt(((',', multispace0).void(), multispace1.void())),
+        ),
+        (multispace0, eof),
+    )
+    .parse_next(input)
+}
+
+fn parse_crate(input: &mut &str) -> ModalResult<Crate> {
+    (parse_int, 'x', parse_int, 'x', parse_int)
+        .map(|(width, _, height, _, depth)| Crate::new(width, height, depth))
+        .parse_next(input)
+}
+
+fn parse_int(input: &mut &str) -> ModalResult<i32> {
+    digit1.parse_to::<i32>().parse_next(input)
+}
+
+/// Whether each crate of `chain` nests in the one before it.
+pub fn is_chain(chain: &[Crate]) -> bool {
+    chain.windows(2).all(|pair| pair[1].fits_in(&pair[0]))
+}
+
+/// A longest sequence of crates that nest into each other, outermost first.
+pub fn longest_chain(crates: &[Crate]) -> Vec<Crate> {
+    let mut sorted = crates.to_vec();
+    // a crate can only nest in crates sorted after it
+    sorted.sort_unstable();
+    // length of the longest chain ending in crate i and the crate it holds
+    let mut length = vec![1; sorted.len()];
+    let mut inner: Vec<Option<usize>> = vec![None; sorted.len()];
+    for i in 0..sorted.len() {
+        for j in 0..i {
+            if sorted[j].fits_in(&sorted[i]) && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                inner[i] = Some(j);
+            }
+        }
+    }
+    let mut chain = Vec::new();
+    let mut next = (0..sorted.len()).max_by_key(|&i| length[i]);
+    while let Some(i) = next {
+        chain.push(sorted[i]);
+        next = inner[i];
+    }
+    chain
+}
+
+/// Packs all crates into as few chains as possible, each outermost first.
+///
+/// By Dilworth's theorem the number of chains equals the largest number of
+/// crates of which none fits in another. Such a cover comes from a maximum
+/// matching between each crate and the crates nested in it, directly or
+/// through others: every matched pair puts the inner crate right after the
+/// outer one in a chain, joining two chains into one. Matching against all
+/// nested crates rather than only the directly held ones is what lets a chain
+/// skip crates that belong to other chains.
+pub fn fewest_chains(crates: &[Crate]) -> Vec<Vec<Crate>> {
+    let mut sorted = crates.to_vec();
+    // outermost first, so a crate can only hold crates sorted after it
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let held_by = maximum_matching(&sorted);
+    let mut holding: Vec<Option<usize>> = vec![None; sorted.len()];
+    for (j, outer) in held_by.iter().enumerate() {
+        if let Some(i) = outer {
+            holding[*i] = Some(j);
+        }
+    }
+    (0..sorted.len())
+        .filter(|&i| held_by[i].is_none())
+        .map(|outermost| {
+            let mut chain = Vec::new();
+            let mut next = Some(outermost);
+            while let Some(i) = next {
+                chain.push(sorted[i]);
+                next = holding[i];
+            }
+            chain
+        })
+        .collect()
+}
+
+/// Matches as many crates as possible with a crate nested in them, using
+/// Hopcroft-Karp. `sorted` must be ordered outermost first. Returns for each
+/// crate the crate it is matched into.
+///
+/// Which crates nest is checked as needed instead of being stored, as there
+/// can be a quadratic number of such pairs. Each phase looks at every pair at
+/// most twice, and the searches keep their own stacks, so neither the memory
+/// nor the stack depth grows with the number of pairs.
+fn maximum_matching(sorted: &[Crate]) -> Vec<Option<usize>> {
+    const UNREACHED: usize = usize::MAX;
+    let n = sorted.len();
+    let holds = |i: usize, j: usize| sorted[j].fits_in(&sorted[i]);
+    let mut held_by: Vec<Option<usize>> = vec![None; n];
+    let mut holding: Vec<Option<usize>> = vec![None; n];
+    loop {
+        // breadth first layers of outer crates along alternating paths,
+        // starting from those holding nothing yet
+        let mut layer = vec![UNREACHED; n];
+        let mut queue: VecDeque<usize> = (0..n).filter(|&i| holding[i].is_none()).collect();
+        for &i in queue.iter() {
+            layer[i] = 0;
+        }
+        // layer of the shortest augmenting paths
+        let mut free_layer = UNREACHED;
+        while let Some(i) = queue.pop_front() {
+            if layer[i] + 1 > free_layer {
+                break;
+            }
+            for j in (i + 1..n).filter(|&j| holds(i, j)) {
+                match held_by[j] {
+                    None => free_layer = free_layer.min(layer[i] + 1),
+                    Some(k) if layer[k] == UNREACHED => {
+                        layer[k] = layer[i] + 1;
+                        queue.push_back(k);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        if free_layer == UNREACHED {
+            return held_by;
+        }
+        // depth first along the layers, each crate resuming where its last
+        // search stopped
+        let mut next: Vec<usize> = (1..=n).collect();
+        for start in 0..n {
+            if layer[start] != 0 {
+                continue;
+            }
+            let mut path = vec![start];
+            // taken[k] is the crate path[k] holds on the path
+            let mut taken: Vec<usize> = Vec::new();
+            while let Some(&i) = path.last() {
+                let candidate = (next[i]..n).find(|&j| {
+                    holds(i, j)
+                        && match held_by[j] {
+                            None => layer[i] + 1 == free_layer,
+                            Some(k) => layer[k] == layer[i] + 1,
+                        }
+                });
+                let Some(j) = candidate else {
+                    next[i] = n;
+                    layer[i] = UNREACHED;
+                    path.pop();
+                    taken.pop();
+                    continue;
+                };
+                next[i] = j + 1;
+                taken.push(j);
+                match held_by[j] {
+                    Some(k) => path.push(k),
+                    None => {
+                        for (&i, &j) in path.iter().zip(taken.iter()) {
+                            held_by[j] = Some(i);
+                            holding[i] = Some(j);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::histogram::SizeHistogram;
+
+    const INPUT: &str = "3x4x5,2x2x2,5x4x3,1x1x1,4x5x6\n7x7x7 2x3x1,3x3x3";
+
+    fn crates() -> Vec<Crate> {
+        parse_crates.parse(INPUT).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let crates = crates();
+        assert_eq!(crates.len(), 8);
+        assert_eq!(crates[6], Crate::new(2, 3, 1));
+        assert_eq!(crates[0].to_string(), "3x4x5");
+        assert!(parse_crates.parse("1x2").is_err());
+        assert!(parse_crates.parse("1x2x3,").is_err());
+    }
+
+    #[test]
+    fn test_longest_chain() {
+        let chain = longest_chain(&crates());
+        assert_eq!(chain.len(), 5);
+        assert!(is_chain(&chain));
+        assert_eq!(chain[0], Crate::new(7, 7, 7));
+        assert!(longest_chain(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_fewest_chains() {
+        let crates = crates();
+        let chains = fewest_chains(&crates);
+        // none of 3x4x5, 5x4x3 and 3x3x3 fits in another
+        assert_eq!(chains.len(), 3);
+        assert!(chains.iter().all(|chain| is_chain(chain)));
+        let mut covered: Vec<Crate> = chains.concat();
+        covered.sort_unstable();
+        let mut expected = crates.clone();
+        expected.sort_unstable();
+        assert_eq!(covered, expected);
+    }
+
+    #[test]
+    fn test_fewest_chains_match_largest_antichain() {
+        // xorshift64 for reproducible random crates
+        let mut state = 88172645463325252u64;
+        let mut random = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 5) as i32
+        };
+        for _ in 0..50 {
+            let crates: Vec<Crate> = (0..10)
+                .map(|_| Crate::new(random(), random(), random()))
+                .collect();
+            let antichain = (0u32..1 << crates.len())
+                .filter(|set| {
+                    let members: Vec<&Crate> = (0..crates.len())
+                        .filter(|i| set & (1 << i) != 0)
+                        .map(|i| &crates[i])
+                        .collect();
+                    members
+                        .iter()
+                        .all(|a| members.iter().all(|b| !a.fits_in(b)))
+                })
+                .map(u32::count_ones)
+                .max()
+                .unwrap();
+            let chains = fewest_chains(&crates);
+            assert_eq!(chains.len(), antichain as usize, "{crates:?}");
+            assert!(chains.iter().all(|chain| is_chain(chain)));
+            assert_eq!(chains.iter().map(Vec::len).sum::<usize>(), crates.len());
+        }
+    }
+
+    #[test]
+    fn test_nested_crates_in_any_order() {
+        let cubes: Vec<Crate> = (1..=2000).map(|s| Crate::new(s, s, s)).collect();
+        let mut reversed = cubes.clone();
+        reversed.reverse();
+        for crates in [cubes, reversed] {
+            let chains = fewest_chains(&crates);
+            assert_eq!(chains.len(), 1);
+            assert_eq!(chains[0].len(), 2000);
+            assert!(is_chain(&chains[0]));
+        }
+    }
+
+    #[test]
+    fn test_cubes_behave_like_sizes() {
+        let sizes = [4, 51, 13, 64, 57, 51, 82, 57, 16, 88, 89, 48, 32, 49, 49, 2];
+        let cubes: Vec<Crate> = sizes.iter().map(|&s| Crate::new(s, s, s)).collect();
+        let histogram = SizeHistogram::from_sizes(sizes);
+        assert_eq!(fewest_chains(&cubes).len(), histogram.max_count());
+        assert_eq!(longest_chain(&cubes).len(), histogram.distinct());
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::{Context, bail};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// Sizes below this are counted in an array, larger ones in a hash map.
+const DENSE_SIZES: usize = 1 << 16;
+
+/// Number of crates of each size.
+///
+/// Puzzle inputs only use small sizes, which are counted in an array indexed
+/// by size that grows up to the largest size seen. Sizes too large for that
+/// go to a hash map, so a single huge size does not cost a huge array.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeHistogram {
+    dense: Vec<usize>,
+    sparse: HashMap<i32, usize>,
+    crates: u64,
+}
+
+impl SizeHistogram {
+    /// Counts the comma separated sizes read from `reader` in one pass,
+    /// without keeping the list of sizes.
+    pub fn read(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut histogram = SizeHistogram::default();
+        let mut size: Option<i32> = None;
+        // set at the start and after each comma, until the next size begins
+        let mut expecting = true;
+        let mut ended = false;
+        loop {
+            let chunk = reader.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            for &byte in chunk {
+                let number = histogram.crates + 1;
+                match byte {
+                    b'0'..=b'9' if !ended => {
+                        let digit = i32::from(byte - b'0');
+                        size = Some(
+                            size.unwrap_or(0)
+                                .checked_mul(10)
+                                .and_then(|size| size.checked_add(digit))
+                                .with_context(|| format!("Crate {number} is too large"))?,
+                        );
+                        expecting = false;
+                    }
+                    b',' if !ended => match size.take() {
+                        Some(size) => {
+                            histogram.add(size);
+                            expecting = true;
+                        }
+                        None => bail!("Crate {number} is missing"),
+                    },
+                    b' ' | b'\t' | b'\r' | b'\n' => {
+                        if let Some(size) = size.take() {
+                            histogram.add(size);
+                        }
+                        ended = true;
+                    }
+                    _ if ended => bail!("Unexpected input after crate {}", number - 1),
+                    _ => bail!("Crate {number}: unexpected {:?}", byte as char),
+                }
+            }
+            let len = chunk.len();
+            reader.consume(len);
+        }
+        if let Some(size) = size {
+            histogram.add(size);
+        } else if expecting {
+            bail!("Crate {} is missing", histogram.crates + 1);
+        }
+        Ok(histogram)
+    }
+
+    /// Counts the sizes of a puzzle input.
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        Self::read(input.as_bytes())
+    }
+
+    /// Counts sizes that are already in memory, such as the crates of
+    /// packed sets.
+    pub fn from_sizes(sizes: impl IntoIterator<Item = i32>) -> Self {
+        let mut histogram = SizeHistogram::default();
+        for size in sizes {
+            histogram.add(size);
+        }
+        histogram
+    }
+
+    pub fn add(&mut self, size: i32) {
+        match usize::try_from(size) {
+            Ok(index) if index < DENSE_SIZES => {
+                if index >= self.dense.len() {
+                    self.dense.resize(index + 1, 0);
+                }
+                self.dense[index] += 1;
+            }
+            _ => *self.sparse.entry(size).or_default() += 1,
+        }
+        self.crates += 1;
+    }
+
+    /// Number of crates of `size`.
+    pub fn count(&self, size: i32) -> usize {
+        match usize::try_from(size) {
+            Ok(index) if index < DENSE_SIZES => self.dense.get(index).copied().unwrap_or(0),
+            _ => self.sparse.get(&size).copied().unwrap_or(0),
+        }
+    }
+
+    /// The sizes that occur with their counts, smallest size first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (i32, usize)> + '_ {
+        let mut sparse: Vec<(i32, usize)> = self.sparse.iter().map(|(&s, &c)| (s, c)).collect();
+        sparse.sort_unstable();
+        // sparse sizes are either negative or above all dense ones
+        let split = sparse.partition_point(|(size, _)| *size < 0);
+        let above = sparse.split_off(split);
+        let dense = self
+            .dense
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(size, &count)| (size as i32, count));
+        sparse.into_iter().chain(dense).chain(above)
+    }
+
+    /// Number of distinct sizes.
+    pub fn distinct(&self) -> usize {
+        self.dense.iter().filter(|count| **count > 0).count() + self.sparse.len()
+    }
+
+    /// Number of crates of the most common size.
+    pub fn max_count(&self) -> usize {
+        let dense = self.dense.iter().copied().max();
+        let sparse = self.sparse.values().copied().max();
+        dense.max(sparse).unwrap_or(0)
+    }
+
+    pub fn crates(&self) -> u64 {
+        self.crates
+    }
+}
+
+/// Writes a puzzle input with `count` random sizes in `1..=max_size`, for
+/// feeding [`SizeHistogram::read`] with arbitrarily large inputs. The same
+/// `seed` always produces the same input. A `count` of zero writes an empty
+/// line, which is not a valid input.
+pub fn write_generated(
+    mut writer: impl Write,
+    count: u64,
+    max_size: i32,
+    seed: u64,
+) -> std::io::Result<()> {
+    // xorshift64, which must not start at zero
+    let mut state = seed.max(1);
+    for i in 0..count {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let size = state % max_size.max(1) as u64 + 1;
+        if i > 0 {
+            write!(writer, ",")?;
+        }
+        write!(writer, "{size}")?;
+    }
+    writeln!(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_read_in_small_chunks() {
+        let input = "10,5,1,10,3,8,5,2,2\n";
+        let histogram = SizeHistogram::read(BufReader::with_capacity(2, input.as_bytes())).unwrap();
+        assert_eq!(histogram, SizeHistogram::parse(input).unwrap());
+        assert_eq!(histogram.crates(), 9);
+        assert_eq!(histogram.distinct(), 6);
+        assert_eq!(histogram.max_count(), 2);
+        assert_eq!(
+            histogram.iter().collect::<Vec<_>>(),
+            [(1, 1), (2, 2), (3, 1), (5, 2), (8, 1), (10, 2)]
+        );
+    }
+
+    #[test]
+    fn test_sparse_sizes() {
+        let mut histogram = SizeHistogram::parse("2000000000,7,2000000000,70000").unwrap();
+        histogram.add(-3);
+        assert_eq!(histogram.dense.len(), 8);
+        assert_eq!(histogram.count(2_000_000_000), 2);
+        assert_eq!(histogram.max_count(), 2);
+        assert_eq!(
+            histogram.iter().rev().collect::<Vec<_>>(),
+            [(2_000_000_000, 2), (70000, 1), (7, 1), (-3, 1)]
+        );
+    }
+
+    #[test]
+    fn test_generated() {
+        let mut input = Vec::new();
+        write_generated(&mut input, 1000, 50, 7).unwrap();
+        let histogram = SizeHistogram::read(input.as_slice()).unwrap();
+        assert_eq!(histogram.crates(), 1000);
+        assert!(histogram.iter().all(|(size, _)| (1..=50).contains(&size)));
+        let mut input = Vec::new();
+        write_generated(&mut input, 100, i32::MAX, 3).unwrap();
+        assert_eq!(SizeHistogram::read(input.as_slice()).unwrap().crates(), 100);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |input: &str| SizeHistogram::parse(input).unwrap_err().to_string();
+        assert_eq!(error(""), "Crate 1 is missing");
+        assert_eq!(error("1,,2"), "Crate 2 is missing");
+        assert_eq!(error("1,2,"), "Crate 3 is missing");
+        assert_eq!(error("1,x"), "Crate 2: unexpected 'x'");
+        assert_eq!(error("1,2\n3"), "Unexpected input after crate 2");
+        assert_eq!(error("3000000000"), "Crate 1 is too large");
+    }
+}
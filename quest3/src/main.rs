@@ -1,66 +1,172 @@
+mod boxes;
+mod histogram;
+mod sets;
+
 use anyhow::anyhow;
-use std::collections::BTreeMap;
-use std::path::Path;
-use winnow::ModalResult;
+use boxes::{fewest_chains, is_chain, longest_chain, parse_crates};
+use common::explain::{EXPLAIN_FLAG, Explain};
+use common::input::read_input;
+use histogram::{SizeHistogram, write_generated};
+use sets::{SetError, largest_set_sum, nested_sets, packing_plan, smallest_set_sum, validate_sets};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
 use winnow::Parser;
-use winnow::ascii::{digit1, multispace0};
-use winnow::combinator::{eof, separated, terminated};
+
+const USAGE: &str = "Usage:
+  quest3 [--explain]                       solve all parts
+  quest3 smallest <k> <input>              total size of the k smallest crates in one set
+  quest3 largest <k> <input>               total size of the k largest crates in one set
+  quest3 pack <input>                      print the fewest sets of nested crates holding all
+  quest3 boxes <input>                     nest WxHxD crates: a longest chain and the fewest
+                                           chains holding all
+  quest3 stream <file>                     answer all parts while reading, keeping only the
+                                           number of crates of each size
+  quest3 generate <count> <max-size> [seed]
+                                           print a random input with count crates of sizes
+                                           1 to max-size";
 
 fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != EXPLAIN_FLAG)
+        .collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] => solve(),
+        ["smallest", k, file] => run_set_sum(k, file, smallest_set_sum),
+        ["largest", k, file] => run_set_sum(k, file, largest_set_sum),
+        ["pack", file] => run_pack(file),
+        ["boxes", file] => run_boxes(file),
+        ["stream", file] => run_stream(file),
+        ["generate", count, max_size] => run_generate(count, max_size, "1"),
+        ["generate", count, max_size, seed] => run_generate(count, max_size, seed),
+        _ => Err(anyhow!("{USAGE}")),
+    }
+}
+
+fn solve() -> anyhow::Result<()> {
+    let mut explain = Explain::from_args();
     let input = read_input("everybody_codes_e2025_q03_p1.txt")?;
-    let result = part1(&input)?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
     let input = read_input("everybody_codes_e2025_q03_p2.txt")?;
-    let result = part2(&input)?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
     let input = read_input("everybody_codes_e2025_q03_p3.txt")?;
-    let result = part3(&input)?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
     Ok(())
 }
 
-fn read_input(path: impl AsRef<Path>) -> anyhow::Result<String> {
-    let result = std::fs::read_to_string(path)?;
-    Ok(result)
+/// Prints the total size of the set of `k` crates from `file` that `set_sum`
+/// picks.
+fn run_set_sum(
+    k: &str,
+    file: &str,
+    set_sum: fn(&SizeHistogram, usize) -> Result<i64, SetError>,
+) -> anyhow::Result<()> {
+    let k: usize = k.parse()?;
+    let crates = SizeHistogram::parse(&read_input(file)?)?;
+    println!("{}", set_sum(&crates, k)?);
+    Ok(())
 }
 
-fn parse_input_data(input: &mut &str) -> ModalResult<Vec<i32>> {
-    terminated(separated(1.., parse_int, ','), (multispace0, eof)).parse_next(input)
+/// Packs the crates of `file` into the fewest sets and prints them.
+fn run_pack(file: &str) -> anyhow::Result<()> {
+    let crates = SizeHistogram::parse(&read_input(file)?)?;
+    let sets = nested_sets(&crates);
+    validate_sets(&crates, &sets)?;
+    print!("{}", packing_plan(&sets));
+    Ok(())
 }
 
-fn parse_int(input: &mut &str) -> ModalResult<i32> {
-    digit1.parse_to::<i32>().parse_next(input)
+/// Prints a longest chain of nested crates and the fewest chains holding all
+/// crates of `file`, which lists crates as `WxHxD`.
+fn run_boxes(file: &str) -> anyhow::Result<()> {
+    let input = read_input(file)?;
+    let crates = parse_crates
+        .parse(input.as_str())
+        .map_err(|e| anyhow!("{e}"))?;
+    let print = |chain: &[boxes::Crate]| {
+        let crates: Vec<String> = chain.iter().map(|c| c.to_string()).collect();
+        format!("({} crates): {}", chain.len(), crates.join(" > "))
+    };
+    println!("longest chain {}", print(&longest_chain(&crates)));
+    let chains = fewest_chains(&crates);
+    if !chains.iter().all(|chain| is_chain(chain)) {
+        return Err(anyhow!("A chain does not nest"));
+    }
+    println!("fewest chains: {}", chains.len());
+    for (i, chain) in chains.iter().enumerate() {
+        println!("chain {} {}", i + 1, print(chain));
+    }
+    Ok(())
 }
 
-fn part1(input: &str) -> anyhow::Result<i32> {
-    let mut inp = input;
-    let mut crates = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    crates.sort_by(|a, b| b.cmp(a));
-    crates.dedup();
-    Ok(crates.iter().sum())
+/// Answers all parts for the plain input `file` while reading it. Only the
+/// number of crates of each size is kept, so memory grows with the number of
+/// distinct sizes rather than with the number of crates.
+fn run_stream(file: &str) -> anyhow::Result<()> {
+    let reader = BufReader::with_capacity(1 << 16, File::open(file)?);
+    let crates = SizeHistogram::read(reader)?;
+    println!("crates: {}", crates.crates());
+    println!("distinct sizes: {}", crates.distinct());
+    println!("{}", largest_set_sum(&crates, crates.distinct())?);
+    println!("{}", smallest_set_sum(&crates, 20)?);
+    println!("{}", crates.max_count());
+    Ok(())
 }
 
-fn part2(input: &str) -> anyhow::Result<i32> {
-    let mut inp = input;
-    let mut crates = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    crates.sort_by(|a, b| b.cmp(a));
-    crates.dedup();
-    let size = crates.len();
-    let start = size - 20;
-    let bla = &crates[start..];
-    assert_eq!(bla.len(), 20);
-    Ok((crates[start..]).iter().sum())
+/// Prints a generated input for `run_stream`.
+fn run_generate(count: &str, max_size: &str, seed: &str) -> anyhow::Result<()> {
+    let count: u64 = count.parse()?;
+    let max_size: i32 = max_size.parse().map_err(|e| {
+        anyhow!(
+            "Invalid maximum size {max_size}, sizes go up to {}: {e}",
+            i32::MAX
+        )
+    })?;
+    if count == 0 || max_size <= 0 {
+        return Err(anyhow!("The count and the maximum size must be positive"));
+    }
+    let mut writer = BufWriter::new(io::stdout().lock());
+    write_generated(&mut writer, count, max_size, seed.parse()?)?;
+    writer.flush()?;
+    Ok(())
 }
 
-fn part3(input: &str) -> anyhow::Result<usize> {
-    let mut inp = input;
-    let crates = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
-    let hist: BTreeMap<i32, usize> = crates.into_iter().fold(BTreeMap::new(), |mut acc, x| {
-        let e = acc.entry(x).or_default();
-        *e += 1;
-        acc
-    });
-    Ok(hist.values().max().copied().unwrap_or_default())
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
+    let crates = SizeHistogram::parse(input)?;
+    explain_sizes(explain, crates.iter().rev());
+    Ok(largest_set_sum(&crates, crates.distinct())?)
+}
+
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
+    let crates = SizeHistogram::parse(input)?;
+    let result = smallest_set_sum(&crates, 20)?;
+    explain_sizes(explain, crates.iter().take(20));
+    Ok(result)
+}
+
+fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
+    let crates = SizeHistogram::parse(input)?;
+    let result = crates.max_count();
+    for (size, count) in crates.iter().filter(|(_, count)| *count == result) {
+        explain.step(|| format!("size {size}: {count} crates, each in its own set"));
+    }
+    Ok(result)
+}
+
+/// Traces the sizes picked for a set, one crate of each.
+fn explain_sizes(explain: &mut Explain, sizes: impl Iterator<Item = (i32, usize)>) {
+    if explain.is_enabled() {
+        for (size, count) in sizes {
+            explain.step(|| format!("size {size}: 1 of {count} crates"));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -70,21 +176,51 @@ mod tests {
     #[test]
     fn test_part1() {
         let input = "10,5,1,10,3,8,5,2,2";
-        let result = part1(input).unwrap();
+        let result = part1(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 29);
     }
 
+    #[test]
+    fn test_explained() {
+        let mut explain = Explain::new(true);
+        part1("10,5,1,10,3", &mut explain).unwrap();
+        assert_eq!(
+            explain.lines(),
+            [
+                "size 10: 1 of 2 crates",
+                "size 5: 1 of 1 crates",
+                "size 3: 1 of 1 crates",
+                "size 1: 1 of 1 crates",
+            ]
+        );
+        let mut explain = Explain::new(true);
+        part3("4,2,4,2,1", &mut explain).unwrap();
+        assert_eq!(
+            explain.lines(),
+            [
+                "size 2: 2 crates, each in its own set",
+                "size 4: 2 crates, each in its own set",
+            ]
+        );
+    }
+
     #[test]
     fn test_part2() {
         let input = "4,51,13,64,57,51,82,57,16,88,89,48,32,49,49,2,84,65,49,43,9,13,2,3,75,72,63,48,61,14,40,77";
-        let result = part2(input).unwrap();
+        let result = part2(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 781);
+        let error = part2("4,51,13,4", &mut Explain::disabled()).unwrap_err();
+        assert!(error.to_string().contains("only 3"));
     }
 
     #[test]
     fn test_part3() {
         let input = "4,51,13,64,57,51,82,57,16,88,89,48,32,49,49,2,84,65,49,43,9,13,2,3,75,72,63,48,61,14,40,77";
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 3);
+        let crates = SizeHistogram::parse(input).unwrap();
+        let sets = nested_sets(&crates);
+        assert_eq!(sets.len(), 3);
+        assert_eq!(validate_sets(&crates, &sets), Ok(()));
     }
 }
@@ -0,0 +1,191 @@
+use crate::histogram::SizeHistogram;
+use std::fmt;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetError {
+    /// A set of `k` crates was asked for but only `distinct` sizes exist.
+    NotEnoughSizes { k: usize, distinct: usize },
+    /// Set `set` holds two crates that do not nest, at `position` and the
+    /// one after it.
+    NotDecreasing { set: usize, position: usize },
+    /// The sets hold `used` crates of `size`, but there are `available`.
+    WrongCount {
+        size: i32,
+        used: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetError::NotEnoughSizes { k, distinct } => write!(
+                f,
+                "a set of {k} crates needs {k} distinct sizes, but there are only {distinct}"
+            ),
+            SetError::NotDecreasing { set, position } => write!(
+                f,
+                "set {set} is not strictly decreasing at position {position}"
+            ),
+            SetError::WrongCount {
+                size,
+                used,
+                available,
+            } => write!(
+                f,
+                "the sets use {used} crates of size {size}, but there are {available}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SetError {}
+
+/// Total size of the `k` smallest crates that nest into one set.
+pub fn smallest_set_sum(crates: &SizeHistogram, k: usize) -> Result<i64, SetError> {
+    check_distinct(crates, k)?;
+    Ok(crates.iter().take(k).map(|(size, _)| i64::from(size)).sum())
+}
+
+/// Total size of the `k` largest crates that nest into one set.
+pub fn largest_set_sum(crates: &SizeHistogram, k: usize) -> Result<i64, SetError> {
+    check_distinct(crates, k)?;
+    Ok(crates
+        .iter()
+        .rev()
+        .take(k)
+        .map(|(size, _)| i64::from(size))
+        .sum())
+}
+
+fn check_distinct(crates: &SizeHistogram, k: usize) -> Result<(), SetError> {
+    let distinct = crates.distinct();
+    if distinct < k {
+        return Err(SetError::NotEnoughSizes { k, distinct });
+    }
+    Ok(())
+}
+
+/// Packs all crates into as few sets of nested crates as possible.
+///
+/// Crates of the same size never nest, so at least as many sets as the most
+/// common size has crates are needed. Handing out the sizes from the largest
+/// down, one crate to each of the first sets, reaches that bound. Each set
+/// lists its crates from the outermost in.
+pub fn nested_sets(crates: &SizeHistogram) -> Vec<Vec<i32>> {
+    let mut sets: Vec<Vec<i32>> = Vec::new();
+    for (size, count) in crates.iter().rev() {
+        if sets.len() < count {
+            sets.resize_with(count, Vec::new);
+        }
+        for set in sets[..count].iter_mut() {
+            set.push(size);
+        }
+    }
+    sets
+}
+
+/// Checks that `sets` are strictly decreasing and together hold exactly the
+/// crates of `crates`.
+pub fn validate_sets(crates: &SizeHistogram, sets: &[Vec<i32>]) -> Result<(), SetError> {
+    for (i, set) in sets.iter().enumerate() {
+        if let Some(position) = set.windows(2).position(|pair| pair[0] <= pair[1]) {
+            return Err(SetError::NotDecreasing { set: i, position });
+        }
+    }
+    let used = SizeHistogram::from_sizes(sets.iter().flatten().copied());
+    for (size, _) in used.iter().chain(crates.iter()) {
+        let (used, available) = (used.count(size), crates.count(size));
+        if used != available {
+            return Err(SetError::WrongCount {
+                size,
+                used,
+                available,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// One line per set, like `set 1 (3 crates): 10 > 5 > 1`.
+pub fn packing_plan(sets: &[Vec<i32>]) -> String {
+    let mut out = String::new();
+    for (i, set) in sets.iter().enumerate() {
+        let crates: Vec<String> = set.iter().map(i32::to_string).collect();
+        writeln!(
+            out,
+            "set {} ({} crates): {}",
+            i + 1,
+            set.len(),
+            crates.join(" > ")
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRATES: [i32; 9] = [10, 5, 1, 10, 3, 8, 5, 2, 2];
+
+    fn crates() -> SizeHistogram {
+        SizeHistogram::from_sizes(CRATES)
+    }
+
+    #[test]
+    fn test_set_sums() {
+        assert_eq!(smallest_set_sum(&crates(), 3), Ok(6));
+        assert_eq!(largest_set_sum(&crates(), 2), Ok(18));
+        assert_eq!(largest_set_sum(&crates(), 6), Ok(29));
+        assert_eq!(smallest_set_sum(&crates(), 0), Ok(0));
+    }
+
+    #[test]
+    fn test_not_enough_sizes() {
+        let error = SetError::NotEnoughSizes { k: 7, distinct: 6 };
+        assert_eq!(smallest_set_sum(&crates(), 7), Err(error.clone()));
+        assert_eq!(largest_set_sum(&crates(), 7), Err(error.clone()));
+        assert_eq!(
+            error.to_string(),
+            "a set of 7 crates needs 7 distinct sizes, but there are only 6"
+        );
+    }
+
+    #[test]
+    fn test_nested_sets() {
+        let sets = nested_sets(&crates());
+        assert_eq!(sets, [vec![10, 8, 5, 3, 2, 1], vec![10, 5, 2]]);
+        assert_eq!(validate_sets(&crates(), &sets), Ok(()));
+        assert_eq!(
+            packing_plan(&sets),
+            "set 1 (6 crates): 10 > 8 > 5 > 3 > 2 > 1\nset 2 (3 crates): 10 > 5 > 2\n"
+        );
+        assert!(nested_sets(&SizeHistogram::default()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_sets() {
+        let sets = [vec![10, 8, 5, 3, 2, 1], vec![10, 5, 5, 2]];
+        assert_eq!(
+            validate_sets(&crates(), &sets),
+            Err(SetError::NotDecreasing {
+                set: 1,
+                position: 1
+            })
+        );
+        let sets = [vec![10, 8, 5, 3, 2, 1], vec![10, 5]];
+        assert_eq!(
+            validate_sets(&crates(), &sets),
+            Err(SetError::WrongCount {
+                size: 2,
+                used: 1,
+                available: 2
+            })
+        );
+        let sets = [vec![10, 8, 5, 3, 2, 1], vec![10, 5, 4, 2]];
+        assert!(validate_sets(&crates(), &sets).is_err());
+    }
+}
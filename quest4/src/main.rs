@@ -1,31 +1,31 @@
 use anyhow::anyhow;
-use std::path::Path;
+use common::explain::Explain;
+use common::input::read_input;
 use winnow::ModalResult;
 use winnow::Parser;
 use winnow::ascii::{digit1, multispace0, multispace1};
 use winnow::combinator::{alt, eof, separated, separated_pair, terminated};
 
 fn main() -> anyhow::Result<()> {
+    let mut explain = Explain::from_args();
     let input = read_input("everybody_codes_e2025_q04_p1.txt")?;
-    let result = part1(&input)?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
 
     let input = read_input("everybody_codes_e2025_q04_p2.txt")?;
-    let result = part2(&input)?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
 
     let input = read_input("everybody_codes_e2025_q04_p3.txt")?;
-    let result = part3(&input)?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
 
     Ok(())
 }
 
-fn read_input(path: impl AsRef<Path>) -> anyhow::Result<String> {
-    let result = std::fs::read_to_string(path)?;
-    Ok(result)
-}
-
 fn parse_input_data(input: &mut &str) -> ModalResult<Vec<f64>> {
     terminated(separated(1.., parse_f64, multispace1), (multispace0, eof)).parse_next(input)
 }
@@ -46,38 +46,53 @@ fn parse_pair(input: &mut &str) -> ModalResult<(f64, f64)> {
     .parse_next(input)
 }
 
-fn part1(input: &str) -> anyhow::Result<i32> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<i32> {
     let mut inp = input;
     let values = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
     let x = values
         .windows(2)
-        .map(|w| w[0] / w[1])
-        .fold(1.0f64, |x, y| x * y)
+        .map(|w| (w[0], w[1]))
+        .fold(1.0f64, |x, (a, b)| chain_ratio(explain, x, a, b))
         * 2025.0f64;
+    explain.step(|| format!("2025 turns -> {x} turns"));
     Ok(x.floor() as i32)
 }
 
-fn part2(input: &str) -> anyhow::Result<i64> {
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
     let mut inp = input;
     let values = parse_input_data(&mut inp).map_err(|e| anyhow!("{e}"))?;
     let x = values
         .windows(2)
-        .map(|w| w[0] / w[1])
-        .fold(1.0f64, |x, y| x * y);
+        .map(|w| (w[0], w[1]))
+        .fold(1.0f64, |x, (a, b)| chain_ratio(explain, x, a, b));
+    explain.step(|| {
+        format!(
+            "10000000000000 turns need {} turns",
+            10000000000000.0f64 / x
+        )
+    });
     Ok((10000000000000.0f64 / x).ceil() as i64)
 }
 
-fn part3(input: &str) -> anyhow::Result<i64> {
+fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
     let mut inp = input;
     let values = parse_pair_list(&mut inp).map_err(|e| anyhow!("{e}"))?;
     let x = values
         .windows(2)
-        .map(|w| w[0].1 / w[1].0)
-        .fold(1.0f64, |x, y| x * y)
+        .map(|w| (w[0].1, w[1].0))
+        .fold(1.0f64, |x, (a, b)| chain_ratio(explain, x, a, b))
         * 100.0f64;
+    explain.step(|| format!("100 turns -> {x} turns"));
     Ok(x.floor() as i64)
 }
 
+/// Multiplies the ratio of the chain so far by the ratio of the gears `a` and `b`.
+fn chain_ratio(explain: &mut Explain, ratio: f64, a: f64, b: f64) -> f64 {
+    let result = ratio * (a / b);
+    explain.step(|| format!("{a} -> {b}: ratio {} (chain {result})", a / b));
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,7 +104,7 @@ mod tests {
 50
 35
 13"#;
-        let result = part1(input).unwrap();
+        let result = part1(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 15888);
     }
 
@@ -100,7 +115,7 @@ mod tests {
 50
 35
 13"#;
-        let result = part2(input).unwrap();
+        let result = part2(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 1274509803922);
     }
 
@@ -113,7 +128,7 @@ mod tests {
 10|50
 10|50
 11"#;
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 6818);
     }
 }
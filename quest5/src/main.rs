@@ -1,5 +1,6 @@
+use common::explain::Explain;
+use common::input::read_input;
 use std::cmp::Ordering;
-use std::path::Path;
 use winnow::ModalResult;
 use winnow::Parser;
 use winnow::ascii::{digit1, multispace0, multispace1};
@@ -12,61 +13,73 @@ macro_rules! full_input {
 }
 
 fn main() -> anyhow::Result<()> {
-    let input = read_file("everybody_codes_e2025_q05_p1.txt")?;
-    let result = part1(&input)?;
+    let mut explain = Explain::from_args();
+    let input = read_input("everybody_codes_e2025_q05_p1.txt")?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
 
-    let input = read_file("everybody_codes_e2025_q05_p2.txt")?;
-    let result = part2(&input)?;
+    let input = read_input("everybody_codes_e2025_q05_p2.txt")?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
 
-    let input = read_file("everybody_codes_e2025_q05_p3.txt")?;
-    let result = part3(&input)?;
+    let input = read_input("everybody_codes_e2025_q05_p3.txt")?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
 
     Ok(())
 }
 
-fn read_file(file_name: impl AsRef<Path>) -> anyhow::Result<String> {
-    let file_name = file_name.as_ref();
-    let contents = std::fs::read_to_string(file_name)?;
-    Ok(contents)
-}
-
-fn part1(input: &str) -> anyhow::Result<i64> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
     let mut inp = input;
     let data = full_input!(parse_sword_data)
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse input data: {}", e))?;
     let fb = Fishbone::from_values(&data.values);
+    explain_fishbone(explain, &data, &fb);
     Ok(fb.quality())
 }
 
-fn part2(input: &str) -> anyhow::Result<i64> {
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
     let mut inp = input;
     let data = full_input!(parse_sword_data_list)
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse input data: {} '{}'", e, inp))?;
     let qualities: Vec<i64> = data
         .iter()
-        .map(|d| Fishbone::from_values(&d.values).quality())
+        .map(|d| {
+            let fb = Fishbone::from_values(&d.values);
+            explain_fishbone(explain, d, &fb);
+            fb.quality()
+        })
         .collect();
     let min_value = qualities.iter().min().copied().unwrap_or_default();
     let max_value = qualities.iter().max().copied().unwrap_or_default();
     Ok(max_value - min_value)
 }
 
-fn part3(input: &str) -> anyhow::Result<i64> {
+fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<i64> {
     let mut inp = input;
     let mut data = full_input!(parse_sword_data_list)
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse input data: {} '{}'", e, inp))?;
     data.sort_by(|a, b| compare_swords(b, a));
+    if explain.is_enabled() {
+        for d in data.iter() {
+            explain_fishbone(explain, d, &Fishbone::from_values(&d.values));
+        }
+        for w in data.windows(2) {
+            let (_, tie_break) = compare_swords_by(&w[0], &w[1]);
+            explain.step(|| format!("sword {} before {}: {tie_break}", w[0].id, w[1].id));
+        }
+    }
     let result = data
         .iter()
         .enumerate()
         .map(|(i, d)| (i, d.id))
-        .inspect(|(i, v)| println!("Sword {}: {}", i + 1, v))
+        .inspect(|(i, v)| explain.step(|| format!("Sword {}: {}", i + 1, v)))
         .map(|(i, v)| ((i + 1) as i64) * v)
         .sum::<i64>();
     Ok(result)
@@ -119,12 +132,17 @@ impl Fishbone {
         for v in self.values.iter().map(|(_, v, _)| *v) {
             s.push_str(&v.to_string());
         }
-        
+
         s.parse::<i64>().expect("cannot parse i64")
     }
 }
 
 fn compare_swords(s1: &SwordData, s2: &SwordData) -> Ordering {
+    compare_swords_by(s1, s2).0
+}
+
+/// Compares two swords and names the criterion that decided the order.
+fn compare_swords_by(s1: &SwordData, s2: &SwordData) -> (Ordering, &'static str) {
     let fb1 = Fishbone::from_values(&s1.values);
     let fb2 = Fishbone::from_values(&s2.values);
     let q1 = fb1.quality();
@@ -132,7 +150,7 @@ fn compare_swords(s1: &SwordData, s2: &SwordData) -> Ordering {
 
     let c = q1.cmp(&q2);
     if c != Ordering::Equal {
-        return c;
+        return (c, "quality");
     }
 
     let l1 = fishbone_values(&fb1.values);
@@ -140,10 +158,21 @@ fn compare_swords(s1: &SwordData, s2: &SwordData) -> Ordering {
 
     let c = l1.cmp(&l2);
     if c != Ordering::Equal {
-        return c;
+        return (c, "levels");
     }
 
-    s1.id.cmp(&s2.id)
+    (s1.id.cmp(&s2.id), "id")
+}
+
+fn explain_fishbone(explain: &mut Explain, sword: &SwordData, fb: &Fishbone) {
+    explain.step(|| {
+        format!(
+            "sword {}: quality {} levels {:?}",
+            sword.id,
+            fb.quality(),
+            fishbone_values(&fb.values)
+        )
+    });
 }
 
 fn fishbone_values(items: &[Item]) -> Vec<i64> {
@@ -160,7 +189,7 @@ fn fishbone_value(item: &Item) -> i64 {
     if let Some(v) = v3 {
         s.push_str(&v.to_string());
     }
-    
+
     s.parse::<i64>().expect("cannot parse")
 }
 
@@ -193,7 +222,7 @@ mod tests {
     #[test]
     fn test_part1() {
         let input = "58:5,3,7,8,9,10,4,5,7,8,8";
-        let result = part1(input).expect("should work");
+        let result = part1(input, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, 581078);
     }
 
@@ -209,7 +238,7 @@ mod tests {
 8:5,1,5,6,8,3,1,8,3,9
 9:5,7,7,3,7,2,3,8,6,7
 10:4,1,9,3,8,5,4,3,5,5"#;
-        let result = part2(input).expect("should work");
+        let result = part2(input, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, 77053);
     }
 
@@ -224,7 +253,7 @@ mod tests {
 7:3,7,2,2,7,4,4,6,3,1
 8:3,7,2,2,7,4,4,6,3,7
 9:3,7,2,2,7,4,1,6,3,7"#;
-        let result = part3(input).expect("should work");
+        let result = part3(input, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, 260);
     }
 
@@ -232,10 +261,23 @@ mod tests {
     fn test_part3_2() {
         let input = r#"1:7,1,9,1,6,9,8,3,7,2
 2:7,1,9,1,6,9,8,3,7,2"#;
-        let result = part3(input).expect("should work");
+        let result = part3(input, &mut Explain::disabled()).expect("should work");
         assert_eq!(result, 4);
     }
 
+    #[test]
+    fn test_part3_explained() {
+        let input = r#"1:7,1,9,1,6,9,8,3,7,2
+2:7,1,9,1,6,9,8,3,7,2"#;
+        let mut explain = Explain::new(true);
+        part3(input, &mut explain).expect("should work");
+        assert!(
+            explain
+                .lines()
+                .contains(&"sword 2 before 1: id".to_string())
+        );
+    }
+
     #[test]
     fn test_hase() {
         let input = "1:5,3,7,8,1,10,9,5,7,8";
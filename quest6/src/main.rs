@@ -1,55 +1,69 @@
-use std::collections::HashMap;
-use std::path::Path;
+use common::explain::Explain;
+use common::input::read_input;
+use std::collections::{BTreeMap, HashMap};
 
 fn main() -> anyhow::Result<()> {
-    let input = read_file("everybody_codes_e2025_q06_p1.txt")?;
-    let result = part1(&input)?;
+    let mut explain = Explain::from_args();
+    let input = read_input("everybody_codes_e2025_q06_p1.txt")?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
 
-    let input = read_file("everybody_codes_e2025_q06_p2.txt")?;
-    let result = part2(&input)?;
+    let input = read_input("everybody_codes_e2025_q06_p2.txt")?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
 
-    let input = read_file("everybody_codes_e2025_q06_p3.txt")?;
-    let result = part3(&input, 1000, 1000)?;
+    let input = read_input("everybody_codes_e2025_q06_p3.txt")?;
+    let result = part3(&input, 1000, 1000, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
 
     Ok(())
 }
 
-fn part1(input: &str) -> anyhow::Result<usize> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut multipliers: HashMap<char, usize> = HashMap::new();
+    let mut pairs: BTreeMap<char, usize> = BTreeMap::new();
     let mut result = 0;
     for c in input.chars().rev().filter(|c| *c == 'a' || *c == 'A') {
         if c.is_ascii_lowercase() {
             *multipliers.entry(c).or_insert(0) += 1;
         } else {
-            result += multipliers
+            let count = multipliers
                 .get(&c.to_ascii_lowercase())
                 .copied()
                 .unwrap_or(0);
+            *pairs.entry(c).or_insert(0) += count;
+            result += count;
         }
     }
+    explain_pairs(explain, &pairs);
     Ok(result)
 }
 
-fn part2(input: &str) -> anyhow::Result<usize> {
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut multipliers: HashMap<char, usize> = HashMap::new();
+    let mut pairs: BTreeMap<char, usize> = BTreeMap::new();
     let mut result = 0;
     for c in input.chars().rev() {
         if c.is_ascii_lowercase() {
             *multipliers.entry(c).or_insert(0) += 1;
         } else {
-            result += multipliers
+            let count = multipliers
                 .get(&c.to_ascii_lowercase())
                 .copied()
                 .unwrap_or(0);
+            *pairs.entry(c).or_insert(0) += count;
+            result += count;
         }
     }
+    explain_pairs(explain, &pairs);
     Ok(result)
 }
 
-fn part3(input: &str, rep: usize, limit: usize) -> anyhow::Result<usize> {
+fn part3(input: &str, rep: usize, limit: usize, explain: &mut Explain) -> anyhow::Result<usize> {
+    let mut pairs: BTreeMap<char, usize> = BTreeMap::new();
     let mut result = 0;
     let chars: Vec<char> = input.to_string().chars().collect();
     let chars = Arr::new(chars, rep);
@@ -61,12 +75,23 @@ fn part3(input: &str, rep: usize, limit: usize) -> anyhow::Result<usize> {
             let count2 = count_to_right(&chars, i, limit);
             let count = count1 + count2;
             // println!("count: {c}: {count1} {count2} {count}");
+            if explain.is_enabled() {
+                *pairs.entry(c.to_ascii_uppercase()).or_insert(0) += count;
+            }
             result += count;
         }
     }
+    explain_pairs(explain, &pairs);
     Ok(result)
 }
 
+/// Traces the number of mentor and novice pairs of each category.
+fn explain_pairs(explain: &mut Explain, pairs: &BTreeMap<char, usize>) {
+    for (category, count) in pairs {
+        explain.step(|| format!("{category}: {count} pairs"));
+    }
+}
+
 fn count_to_left(chars: &Arr, start: usize, limit: usize) -> usize {
     let ch = chars.get(start);
     if ch.is_ascii_uppercase() {
@@ -108,11 +133,6 @@ fn count_to_right(chars: &Arr, start: usize, limit: usize) -> usize {
     result
 }
 
-fn read_file(filename: impl AsRef<Path>) -> anyhow::Result<String> {
-    let content = std::fs::read_to_string(filename)?;
-    Ok(content)
-}
-
 #[derive(Debug)]
 struct Arr {
     chars: Vec<char>,
@@ -141,7 +161,7 @@ mod tests {
     fn test_part3() {
         let input = "AABCBABCABCabcabcABCCBAACBCa";
         let limit = 10;
-        let result = part3(input, 1, limit).unwrap();
+        let result = part3(input, 1, limit, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 34);
     }
 
@@ -149,11 +169,12 @@ mod tests {
     fn test_part3_2() {
         let input = "AABCBABCABCabcabcABCCBAACBCa";
         let limit = 10;
-        let result = part3(input, 2, limit).unwrap();
+        let result = part3(input, 2, limit, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 72);
     }
 
     #[test]
+    #[allow(unused_variables)]
     fn test_count_to_left() {
         let input = Arr::new(
             "AABCBABCABCabcabcABCCBAACBCa"
@@ -168,6 +189,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(unused_variables)]
     fn test_count_to_right() {
         let input = Arr::new(
             "AABCBABCABCabcabcABCCBAACBCa"
@@ -184,7 +206,15 @@ mod tests {
     #[test]
     fn test_part1() {
         let input = "ABabACacBCbca";
-        let result = part1(input).unwrap();
+        let result = part1(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 5);
     }
+
+    #[test]
+    fn test_part2_explained() {
+        let mut explain = Explain::new(true);
+        let result = part2("ABabACacBCbca", &mut explain).unwrap();
+        assert_eq!(result, 11);
+        assert_eq!(explain.lines(), ["A: 5 pairs", "B: 3 pairs", "C: 3 pairs"]);
+    }
 }
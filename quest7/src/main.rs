@@ -1,7 +1,9 @@
 use anyhow::anyhow;
+use common::explain::Explain;
+use common::graph::{AdjacencyList, Graph, ImplicitGraph, bfs};
+use common::input::read_input;
 use itertools::Itertools;
-use std::collections::{BTreeMap, HashSet, VecDeque};
-use std::path::Path;
+use std::collections::BTreeMap;
 use winnow::ascii::{alpha1, line_ending, multispace0, multispace1, space0};
 use winnow::combinator::{eof, separated, separated_pair, terminated};
 use winnow::token::any;
@@ -14,26 +16,31 @@ macro_rules! full_input {
 }
 
 fn main() -> anyhow::Result<()> {
-    let input = read_file("everybody_codes_e2025_q07_p1.txt")?;
-    let result = part1(&input)?;
+    let mut explain = Explain::from_args();
+    let input = read_input("everybody_codes_e2025_q07_p1.txt")?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
 
-    let input = read_file("everybody_codes_e2025_q07_p2.txt")?;
-    let result = part2(&input)?;
+    let input = read_input("everybody_codes_e2025_q07_p2.txt")?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
 
-    let input = read_file("everybody_codes_e2025_q07_p3.txt")?;
-    let result = part3(&input)?;
+    let input = read_input("everybody_codes_e2025_q07_p3.txt")?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
 
     Ok(())
 }
 
-fn part1(input: &str) -> anyhow::Result<String> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<String> {
     let mut inp = input;
     let input_data = full_input!(parse_input_data)
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse input data: {}", e))?;
+    explain_names(explain, &input_data);
     let result = input_data
         .names
         .into_iter()
@@ -42,11 +49,12 @@ fn part1(input: &str) -> anyhow::Result<String> {
     Ok(result)
 }
 
-fn part2(input: &str) -> anyhow::Result<usize> {
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut inp = input;
     let input_data = full_input!(parse_input_data)
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse input data: {}", e))?;
+    explain_names(explain, &input_data);
     let result = input_data
         .names
         .iter()
@@ -57,60 +65,71 @@ fn part2(input: &str) -> anyhow::Result<usize> {
     Ok(result)
 }
 
-fn part3(input: &str) -> anyhow::Result<usize> {
+fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut inp = input;
     let input_data = full_input!(parse_input_data)
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse input data: {}", e))?;
+    explain_names(explain, &input_data);
     let names: Vec<String> = input_data
         .names
         .iter()
         .filter(|name| matches_word(name, &input_data.adj))
         .cloned()
         .collect();
-    let mut q: VecDeque<String> = VecDeque::from(names);
-    let mut result = HashSet::new();
-
-    while let Some(current) = q.pop_front() {
-        if current.len() > 11 {
-            continue;
-        }
-
-        if current.len() >= 7 {
-            result.insert(current.to_string());
-        }
-
-        if current.len() == 11 {
-            continue;
+    let graph = ImplicitGraph::new(|current: &String| {
+        if current.len() >= 11 {
+            return Vec::new();
         }
-
         let c = current.chars().last().unwrap();
-        let Some(next) = input_data.adj.get(&c) else {
-            continue;
-        };
-        for next_c in next.iter() {
-            q.push_back(format!("{}{}", current, next_c));
+        input_data
+            .adj
+            .neighbors(&c)
+            .map(|next_c| format!("{}{}", current, next_c))
+            .collect()
+    });
+    let names: Vec<String> = bfs(&graph, names)
+        .into_iter()
+        .filter(|name| (7..=11).contains(&name.len()))
+        .collect();
+    if explain.is_enabled() {
+        let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+        for name in names.iter() {
+            *lengths.entry(name.len()).or_insert(0) += 1;
+        }
+        for (len, count) in lengths {
+            explain.step(|| format!("{count} names of length {len}"));
         }
     }
-    Ok(result.len())
+    Ok(names.len())
 }
 
-fn matches_word(word: &str, adj: &BTreeMap<char, Vec<char>>) -> bool {
-    for (c1, c2) in word.chars().tuple_windows::<(char, char)>() {
-        let Some(next) = adj.get(&c1) else {
-            return false;
-        };
-        if !next.contains(&c2) {
-            return false;
-        }
+fn matches_word(word: &str, adj: &AdjacencyList<char>) -> bool {
+    first_violation(word, adj).is_none()
+}
+
+/// The first pair of letters of `word` that the rules do not allow, if any.
+fn first_violation(word: &str, adj: &AdjacencyList<char>) -> Option<(char, char)> {
+    word.chars()
+        .tuple_windows::<(char, char)>()
+        .find(|(c1, c2)| !adj.contains_edge(c1, c2))
+}
+
+/// Traces which names follow the rules, and for the others the first pair
+/// of letters that breaks them.
+fn explain_names(explain: &mut Explain, input_data: &InputData) {
+    for name in input_data.names.iter() {
+        explain.step(|| match first_violation(name, &input_data.adj) {
+            None => format!("{name}: matches"),
+            Some((c1, c2)) => format!("{name}: {c1} > {c2} not allowed"),
+        });
     }
-    true
 }
 
 #[derive(Debug)]
 struct InputData {
     names: Vec<String>,
-    adj: BTreeMap<char, Vec<char>>,
+    adj: AdjacencyList<char>,
 }
 
 fn parse_input_data(input: &mut &str) -> ModalResult<InputData> {
@@ -123,10 +142,10 @@ fn parse_names(input: &mut &str) -> ModalResult<Vec<String>> {
     separated(1.., alpha1.map(|s: &str| s.to_string()), ',').parse_next(input)
 }
 
-fn parse_adj_list(input: &mut &str) -> ModalResult<BTreeMap<char, Vec<char>>> {
+fn parse_adj_list(input: &mut &str) -> ModalResult<AdjacencyList<char>> {
     let entries: Vec<(char, Vec<char>)> =
         separated(1.., parse_adj_entry, line_ending).parse_next(input)?;
-    let result = BTreeMap::from_iter(entries);
+    let result = AdjacencyList::from_iter(entries);
     Ok(result)
 }
 
@@ -142,11 +161,6 @@ fn parse_alpha(input: &mut &str) -> ModalResult<char> {
     any.verify(|c: &char| c.is_alphabetic()).parse_next(input)
 }
 
-fn read_file(filename: impl AsRef<Path>) -> anyhow::Result<String> {
-    let content = std::fs::read_to_string(filename)?;
-    Ok(content)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,7 +179,7 @@ U > r
 e > t
 O > r
 t > h"#;
-        let result = part1(input).unwrap();
+        let result = part1(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, "Oroneth");
     }
 
@@ -193,7 +207,7 @@ X > a
 n > v
 x > z
 T > i"#;
-        let result = part2(input).unwrap();
+        let result = part2(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 23);
     }
 
@@ -209,10 +223,29 @@ t > h
 v > e
 y > p,t"#;
 
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 25);
     }
 
+    #[test]
+    fn test_part3_explained() {
+        let input = "Xaryt,Xo\n\nX > a\na > r,t\nr > y,e,a\nh > a,e,v\nt > h\nv > e\ny > p,t";
+        let mut explain = Explain::new(true);
+        assert_eq!(part3(input, &mut explain).unwrap(), 25);
+        assert_eq!(
+            explain.lines(),
+            [
+                "Xaryt: matches",
+                "Xo: X > o not allowed",
+                "3 names of length 7",
+                "3 names of length 8",
+                "4 names of length 9",
+                "7 names of length 10",
+                "8 names of length 11",
+            ]
+        );
+    }
+
     #[test]
     fn test_part3_2() {
         let input = r#"Khara,Xaryt,Noxer,Kharax
@@ -238,7 +271,7 @@ n > v
 x > z
 T > i"#;
 
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 1154);
     }
 }
@@ -1,27 +1,33 @@
+use common::explain::Explain;
+use common::geometry::chords_cross;
+use common::input::read_input;
 use std::collections::BTreeMap;
-use std::fs::read_to_string;
 use winnow::ModalResult;
 use winnow::Parser;
 use winnow::ascii::{digit1, multispace0};
 use winnow::combinator::{eof, separated, terminated};
 
 fn main() -> anyhow::Result<()> {
-    let input = read_to_string("everybody_codes_e2025_q08_p1.txt")?;
-    let result = part1(&input)?;
+    let mut explain = Explain::from_args();
+    let input = read_input("everybody_codes_e2025_q08_p1.txt")?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
 
-    let input = read_to_string("everybody_codes_e2025_q08_p2.txt")?;
-    let result = part2(&input)?;
+    let input = read_input("everybody_codes_e2025_q08_p2.txt")?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
 
-    let input = read_to_string("everybody_codes_e2025_q08_p3.txt")?;
-    let result = part3(&input)?;
+    let input = read_input("everybody_codes_e2025_q08_p3.txt")?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
 
     Ok(())
 }
 
-fn part1(input: &str) -> anyhow::Result<usize> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut inp = input;
     let input_data = terminated(parse_int_list, (multispace0, eof))
         .parse_next(&mut inp)
@@ -32,11 +38,12 @@ fn part1(input: &str) -> anyhow::Result<usize> {
         .as_slice()
         .windows(2)
         .filter(|w| (w[1] - w[0]).abs() == want)
+        .inspect(|w| explain.step(|| format!("{}-{} passes through the center", w[0], w[1])))
         .count();
     Ok(result)
 }
 
-fn part2(input: &str) -> anyhow::Result<usize> {
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut result = 0;
     let mut inp = input;
     let input_data = terminated(parse_int_list, (multispace0, eof))
@@ -49,27 +56,24 @@ fn part2(input: &str) -> anyhow::Result<usize> {
         .collect();
 
     for (i, (x, y)) in pairs.iter().enumerate() {
+        let mut crossed = 0;
         for (a, b) in pairs.iter().skip(i + 1) {
             if overlaps(*a, *b, *x, *y) {
-                result += 1;
+                crossed += 1;
             }
         }
+        explain.step(|| format!("{x}-{y} crosses {crossed} later threads"));
+        result += crossed;
     }
 
     Ok(result)
 }
 
 pub fn overlaps(a: i32, b: i32, x: i32, y: i32) -> bool {
-    // strings with equal endpoints do not overlap
-    if a == x || a == y || b == x || b == y {
-        return false;
-    }
-    // one point is within the interval of the other.
-    // one point is not within the interval of the other.
-    (a < x && x < b) != (a < y && y < b)
+    chords_cross((a, b), (x, y))
 }
 
-pub fn part3(input: &str) -> anyhow::Result<i32> {
+pub fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<i32> {
     let mut inp = input;
     let input_data = terminated(parse_int_list, (multispace0, eof))
         .parse_next(&mut inp)
@@ -91,7 +95,11 @@ pub fn part3(input: &str) -> anyhow::Result<i32> {
         }
     }
 
-    Ok(counts.values().max().copied().unwrap_or_default())
+    let result = counts.values().max().copied().unwrap_or_default();
+    for ((a, b), count) in counts.iter().filter(|(_, count)| **count == result) {
+        explain.step(|| format!("cutting {a}-{b} cuts {count} threads"));
+    }
+    Ok(result)
 }
 
 fn parse_int_list(input: &mut &str) -> ModalResult<Vec<i32>> {
@@ -109,21 +117,42 @@ mod tests {
     #[test]
     fn test_part1() {
         let input = "1,5,2,6,8,4,1,7,3";
-        let result = part1(input).unwrap();
+        let result = part1(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 4);
     }
 
     #[test]
     fn test_part2() {
         let input = "1,5,2,6,8,4,1,7,3,5,7,8,2";
-        let result = part2(input).unwrap();
+        let result = part2(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 21);
     }
 
     #[test]
     fn test_part3() {
         let input = "1,5,2,6,8,4,1,7,3,6";
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 6);
     }
+
+    #[test]
+    fn test_explained() {
+        let mut explain = Explain::new(true);
+        part1("1,5,2,6,8,4,1,7,3", &mut explain).unwrap();
+        assert_eq!(
+            explain.lines(),
+            [
+                "1-5 passes through the center",
+                "2-6 passes through the center",
+                "8-4 passes through the center",
+                "7-3 passes through the center",
+            ]
+        );
+        let mut explain = Explain::new(true);
+        part3("1,5,2,6,8,4,1,7,3,6", &mut explain).unwrap();
+        assert_eq!(
+            explain.lines(),
+            ["cutting 3-7 cuts 6 threads", "cutting 4-8 cuts 6 threads"]
+        );
+    }
 }
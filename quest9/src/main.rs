@@ -1,6 +1,7 @@
+use common::explain::Explain;
+use common::graph::{AdjacencyList, connected_components};
+use common::input::read_input;
 use itertools::izip;
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
-use std::fs::read_to_string;
 use winnow::ModalResult;
 use winnow::Parser;
 use winnow::ascii::{digit1, line_ending, multispace0};
@@ -9,32 +10,37 @@ use winnow::combinator::{repeat, separated};
 use winnow::token::one_of;
 
 fn main() -> anyhow::Result<()> {
-    let input = read_to_string("everybody_codes_e2025_q09_p1.txt")?;
-    let result = part1(&input)?;
+    let mut explain = Explain::from_args();
+    let input = read_input("everybody_codes_e2025_q09_p1.txt")?;
+    let result = part1(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 1");
 
-    let input = read_to_string("everybody_codes_e2025_q09_p2.txt")?;
-    let result = part2(&input)?;
+    let input = read_input("everybody_codes_e2025_q09_p2.txt")?;
+    let result = part2(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 2");
 
-    let input = read_to_string("everybody_codes_e2025_q09_p3.txt")?;
-    let result = part3(&input)?;
+    let input = read_input("everybody_codes_e2025_q09_p3.txt")?;
+    let result = part3(&input, &mut explain)?;
     println!("{result}");
+    explain.print("part 3");
 
     Ok(())
 }
 
-fn part1(input: &str) -> anyhow::Result<usize> {
+fn part1(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut inp = input;
     let input_data = terminated(parse_sequences, (multispace0, eof))
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse sequence: {}", e))?;
     let degree1 = similarity_degree(&input_data[0], &input_data[2]);
     let degree2 = similarity_degree(&input_data[1], &input_data[2]);
+    explain.step(|| format!("similarity degrees {degree1} and {degree2}"));
     Ok(degree1 * degree2)
 }
 
-fn part2(input: &str) -> anyhow::Result<usize> {
+fn part2(input: &str, explain: &mut Explain) -> anyhow::Result<usize> {
     let mut result = 0;
     let mut inp = input;
     let input_data = terminated(parse_sequences, (multispace0, eof))
@@ -52,6 +58,7 @@ fn part2(input: &str) -> anyhow::Result<usize> {
                 {
                     let degree1 = similarity_degree(a, c);
                     let degree2 = similarity_degree(b, c);
+                    explain_parents(explain, a, b, c);
                     result += degree1 * degree2;
                 }
             }
@@ -60,12 +67,12 @@ fn part2(input: &str) -> anyhow::Result<usize> {
     Ok(result)
 }
 
-fn part3(input: &str) -> anyhow::Result<i32> {
+fn part3(input: &str, explain: &mut Explain) -> anyhow::Result<i32> {
     let mut inp = input;
     let input_data = terminated(parse_sequences, (multispace0, eof))
         .parse_next(&mut inp)
         .map_err(|e| anyhow::anyhow!("Failed to parse sequence: {}", e))?;
-    let mut adj: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    let mut adj: AdjacencyList<i32> = AdjacencyList::new();
 
     for (i, a) in input_data.iter().enumerate() {
         for (j, b) in input_data.iter().enumerate().skip(i + 1) {
@@ -77,41 +84,35 @@ fn part3(input: &str) -> anyhow::Result<i32> {
                 if izip!(a.symbols.iter(), b.symbols.iter(), c.symbols.iter())
                     .all(|(ac, bc, cc)| ac == cc || bc == cc)
                 {
-                    adj.entry(a.id).or_default().push(c.id);
-                    adj.entry(b.id).or_default().push(c.id);
-                    adj.entry(c.id).or_default().push(a.id);
-                    adj.entry(c.id).or_default().push(b.id);
+                    explain_parents(explain, a, b, c);
+                    adj.add_undirected_edge(a.id, c.id);
+                    adj.add_undirected_edge(b.id, c.id);
                 }
             }
         }
     }
 
-    let mut visited = BTreeSet::<i32>::new();
     let empty: Vec<i32> = Vec::new();
-    let mut families: Vec<Vec<i32>> = Vec::new();
-    for seq in input_data.iter() {
-        if visited.contains(&seq.id) {
-            continue;
-        }
-        let mut q = VecDeque::from([seq.id]);
-        let mut curr: Vec<i32> = Vec::new();
-        while let Some(i) = q.pop_front() {
-            if !visited.insert(i) {
-                continue;
-            }
-            curr.push(i);
-            let neighbors = adj.get(&i).unwrap_or(&empty);
-            for j in neighbors.iter().filter(|&j| !visited.contains(j)) {
-                q.push_back(*j);
-            }
-        }
-        families.push(curr);
-    }
+    let families = connected_components(&adj, input_data.iter().map(|seq| seq.id));
     let best = families.iter().max_by_key(|f| f.len()).unwrap_or(&empty);
+    explain.step(|| format!("largest family {best:?}"));
     let result = best.iter().sum();
     Ok(result)
 }
 
+fn explain_parents(explain: &mut Explain, a: &Sequence, b: &Sequence, c: &Sequence) {
+    explain.step(|| {
+        format!(
+            "child {} has parents {} and {} (degrees {} and {})",
+            c.id,
+            a.id,
+            b.id,
+            similarity_degree(a, c),
+            similarity_degree(b, c)
+        )
+    });
+}
+
 fn similarity_degree(seq1: &Sequence, seq2: &Sequence) -> usize {
     seq1.symbols
         .iter()
@@ -149,7 +150,7 @@ mod tests {
         let input = r#"1:CAAGCGCTAAGTTCGCTGGATGTGTGCCCGCG
 2:CTTGAATTGGGCCGTTTACCTGGTTTAACCAT
 3:CTAGCGCTGAGCTGGCTGCCTGGTTGACCGCG"#;
-        let result = part1(input).unwrap();
+        let result = part1(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 414);
     }
 
@@ -162,7 +163,7 @@ mod tests {
 5:GCAGCTTAGTATGACCGCCAAATCGCGACTCA
 6:AGTGGAACCTTGGATAGTCTCATATAGCGGCA
 7:GGCGTAATAATCGGATGCTGCAGAGGCTGCTG"#;
-        let result = part2(input).unwrap();
+        let result = part2(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 1245);
     }
 
@@ -175,7 +176,7 @@ mod tests {
 5:GCAGCTTAGTATGACCGCCAAATCGCGACTCA
 6:AGTGGAACCTTGGATAGTCTCATATAGCGGCA
 7:GGCGTAATAATCGGATGCTGCAGAGGCTGCTG"#;
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 12);
     }
 
@@ -189,7 +190,7 @@ mod tests {
 6:AGTGGAACCTTGGATAGTCTCATATAGCGGCA
 7:GGCGTAATAATCGGATGCTGCAGAGGCTGCTG
 8:GGCGTAAAGTATGGATGCTGGCTAGGCACCCG"#;
-        let result = part3(input).unwrap();
+        let result = part3(input, &mut Explain::disabled()).unwrap();
         assert_eq!(result, 36);
     }
 }
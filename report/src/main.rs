@@ -0,0 +1,42 @@
+mod manifest;
+mod registry;
+mod render;
+
+use anyhow::anyhow;
+use std::path::PathBuf;
+
+use render::{Format, Report};
+
+/// Writes a progress report of all quests in the workspace.
+///
+/// Usage: `report [--root DIR] [--format md|html] [--output FILE]`
+fn main() -> anyhow::Result<()> {
+    let mut root = PathBuf::from(".");
+    let mut format = Format::Markdown;
+    let mut output: Option<PathBuf> = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--root" => root = PathBuf::from(value()?),
+            "--format" => format = Format::from_name(&value()?)?,
+            "--output" => output = Some(PathBuf::from(value()?)),
+            _ => return Err(anyhow!("Unknown argument: {arg}")),
+        }
+    }
+
+    let quests = registry::discover(&root)?;
+    let answers = manifest::read_answers(&root.join(manifest::ANSWERS_FILE))?;
+    let benchmarks = manifest::read_benchmarks(&root.join(manifest::BENCHMARKS_FILE))?;
+    let report = Report::new(&quests, &answers, &benchmarks).render(format);
+
+    match output {
+        Some(path) => std::fs::write(path, report)?,
+        None => print!("{report}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Line based manifests keyed by quest and part.
+//!
+//! Both the answers manifest and the benchmark results use the same layout:
+//!
+//! ```text
+//! # quest part value
+//! 1 1 Fyrryn
+//! 2 1 [357,862]
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored.
+
+use anyhow::anyhow;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use winnow::ModalResult;
+use winnow::Parser;
+use winnow::ascii::{digit1, float, space0, space1};
+use winnow::combinator::{alt, eof, preceded, terminated};
+use winnow::token::rest;
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+pub const BENCHMARKS_FILE: &str = "benchmarks.txt";
+
+pub type Key = (u32, u32);
+
+pub fn read_answers(path: &Path) -> anyhow::Result<BTreeMap<Key, String>> {
+    read_manifest(path, |inp| {
+        rest.map(|s: &str| s.trim().to_string()).parse_next(inp)
+    })
+}
+
+pub fn read_benchmarks(path: &Path) -> anyhow::Result<BTreeMap<Key, Duration>> {
+    read_manifest(path, |inp| {
+        terminated(parse_duration, (space0, eof)).parse_next(inp)
+    })
+}
+
+/// Reads a manifest, treating a missing file as an empty manifest.
+fn read_manifest<T>(
+    path: &Path,
+    value: impl FnMut(&mut &str) -> ModalResult<T>,
+) -> anyhow::Result<BTreeMap<Key, T>> {
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+    let contents = std::fs::read_to_string(path)?;
+    parse_manifest(&contents, value).map_err(|e| anyhow!("Failed to parse {}: {e}", path.display()))
+}
+
+fn parse_manifest<T>(
+    input: &str,
+    mut value: impl FnMut(&mut &str) -> ModalResult<T>,
+) -> anyhow::Result<BTreeMap<Key, T>> {
+    let mut result = BTreeMap::new();
+    for (line_no, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut inp = line;
+        let (key, v) = (parse_key, preceded(space1, value.by_ref()))
+            .parse_next(&mut inp)
+            .map_err(|e| anyhow!("line {}: {e} '{line}'", line_no + 1))?;
+        result.insert(key, v);
+    }
+    Ok(result)
+}
+
+fn parse_key(input: &mut &str) -> ModalResult<Key> {
+    (parse_int, preceded(space1, parse_int)).parse_next(input)
+}
+
+fn parse_int(input: &mut &str) -> ModalResult<u32> {
+    digit1.parse_to::<u32>().parse_next(input)
+}
+
+fn parse_duration(input: &mut &str) -> ModalResult<Duration> {
+    (float::<_, f64, _>, space0, parse_unit)
+        .try_map(|(value, _, scale)| Duration::try_from_secs_f64(value * scale))
+        .parse_next(input)
+}
+
+fn parse_unit(input: &mut &str) -> ModalResult<f64> {
+    alt((
+        "ns".value(1e-9),
+        "us".value(1e-6),
+        "µs".value(1e-6),
+        "ms".value(1e-3),
+        "s".value(1.0),
+    ))
+    .parse_next(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let input = r#"# quest part answer
+1 1 Fyrryn
+
+2 1 [357,862]
+"#;
+        let result = parse_manifest(input, |inp| {
+            rest.map(|s: &str| s.trim().to_string()).parse_next(inp)
+        })
+        .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[&(1, 1)], "Fyrryn");
+        assert_eq!(result[&(2, 1)], "[357,862]");
+    }
+
+    #[test]
+    fn test_parse_benchmarks() {
+        let input = "1 1 12ns\n1 2 1.5 ms\n2 3 2s";
+        let result = parse_manifest(input, |inp| {
+            terminated(parse_duration, (space0, eof)).parse_next(inp)
+        })
+        .unwrap();
+        assert_eq!(result[&(1, 1)], Duration::from_nanos(12));
+        assert_eq!(result[&(1, 2)], Duration::from_micros(1500));
+        assert_eq!(result[&(2, 3)], Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_parse_error_reports_line() {
+        let input = "1 1 12ns\nfoo";
+        let result = parse_manifest(input, |inp| {
+            terminated(parse_duration, (space0, eof)).parse_next(inp)
+        });
+        let err = result.unwrap_err().to_string();
+        assert!(err.starts_with("line 2:"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_durations() {
+        for input in ["-1s", "inf ms", "NaN s", "1e300 s"] {
+            let result = terminated(parse_duration, eof).parse(input);
+            assert!(result.is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+pub const PARTS_PER_QUEST: u32 = 3;
+
+/// A quest crate of the workspace together with the inputs of its parts.
+#[derive(Debug, Clone)]
+pub struct Quest {
+    pub number: u32,
+    pub parts: Vec<Part>,
+    pub test_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub number: u32,
+    pub input_file: PathBuf,
+}
+
+impl Part {
+    pub fn has_input(&self) -> bool {
+        self.input_file.is_file() || common::input::encrypted_path(&self.input_file).is_file()
+    }
+}
+
+/// Collects all `questN` crates below `root`, ordered by quest number.
+pub fn discover(root: &Path) -> anyhow::Result<Vec<Quest>> {
+    let mut quests = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(number) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix("quest"))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let crate_dir = entry.path();
+        if !crate_dir.join("Cargo.toml").is_file() {
+            continue;
+        }
+        let parts = (1..=PARTS_PER_QUEST)
+            .map(|part| Part {
+                number: part,
+                input_file: root.join(input_file_name(number, part)),
+            })
+            .collect();
+        let test_count = count_tests(&crate_dir.join("src"))?;
+        quests.push(Quest {
+            number,
+            parts,
+            test_count,
+        });
+    }
+    quests.sort_by_key(|q| q.number);
+    Ok(quests)
+}
+
+pub fn input_file_name(quest: u32, part: u32) -> String {
+    format!("everybody_codes_e2025_q{quest:02}_p{part}.txt")
+}
+
+fn count_tests(dir: &Path) -> anyhow::Result<usize> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            count += count_tests(&path)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let contents = std::fs::read_to_string(&path)?;
+            count += count_test_attributes(&contents);
+        }
+    }
+    Ok(count)
+}
+
+fn count_test_attributes(source: &str) -> usize {
+    source
+        .lines()
+        .filter(|line| line.trim() == "#[test]")
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_file_name() {
+        assert_eq!(input_file_name(1, 3), "everybody_codes_e2025_q01_p3.txt");
+        assert_eq!(input_file_name(12, 1), "everybody_codes_e2025_q12_p1.txt");
+    }
+
+    #[test]
+    fn test_count_test_attributes() {
+        let source = r#"
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn a() {}
+
+    #[test]
+    fn b() {}
+}"#;
+        assert_eq!(count_test_attributes(source), 2);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::manifest::Key;
+use crate::registry::Quest;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> anyhow::Result<Format> {
+        match name {
+            "md" | "markdown" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            _ => Err(anyhow::anyhow!("Unknown report format: {name}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Solved,
+    Unsolved,
+    NoInput,
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Solved => "solved",
+            Status::Unsolved => "unsolved",
+            Status::NoInput => "no input",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Row {
+    quest: u32,
+    part: u32,
+    status: Status,
+    answer: Option<String>,
+    time: Option<Duration>,
+}
+
+/// Everything the report shows, joined from the registry and both manifests.
+#[derive(Debug)]
+pub struct Report {
+    quests: Vec<(u32, usize, usize)>,
+    rows: Vec<Row>,
+}
+
+impl Report {
+    pub fn new(
+        quests: &[Quest],
+        answers: &BTreeMap<Key, String>,
+        benchmarks: &BTreeMap<Key, Duration>,
+    ) -> Self {
+        let mut rows = Vec::new();
+        let mut summary = Vec::new();
+        for quest in quests {
+            let mut solved = 0;
+            for part in quest.parts.iter() {
+                let key = (quest.number, part.number);
+                let answer = answers.get(&key).cloned();
+                let status = match (&answer, part.has_input()) {
+                    (Some(_), _) => Status::Solved,
+                    (None, true) => Status::Unsolved,
+                    (None, false) => Status::NoInput,
+                };
+                if status == Status::Solved {
+                    solved += 1;
+                }
+                rows.push(Row {
+                    quest: quest.number,
+                    part: part.number,
+                    status,
+                    answer,
+                    time: benchmarks.get(&key).copied(),
+                });
+            }
+            summary.push((quest.number, solved, quest.test_count));
+        }
+        Report {
+            quests: summary,
+            rows,
+        }
+    }
+
+    fn solved(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| r.status == Status::Solved)
+            .count()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Markdown => self.to_markdown(),
+            Format::Html => self.to_html(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "# Everybody Codes 2025 progress").unwrap();
+        writeln!(s).unwrap();
+        writeln!(s, "Solved {} of {} parts.", self.solved(), self.rows.len()).unwrap();
+        writeln!(s).unwrap();
+        writeln!(s, "| Quest | Solved | Tests |").unwrap();
+        writeln!(s, "|------:|-------:|------:|").unwrap();
+        for (quest, solved, tests) in self.quests.iter() {
+            writeln!(s, "| {quest} | {solved} | {tests} |").unwrap();
+        }
+        writeln!(s).unwrap();
+        writeln!(s, "| Quest | Part | Status | Answer | Run time |").unwrap();
+        writeln!(s, "|------:|-----:|--------|--------|---------:|").unwrap();
+        for row in self.rows.iter() {
+            writeln!(
+                s,
+                "| {} | {} | {} | {} | {} |",
+                row.quest,
+                row.part,
+                row.status.label(),
+                row.answer.as_deref().unwrap_or("-").replace('|', "\\|"),
+                format_time(row.time),
+            )
+            .unwrap();
+        }
+        s
+    }
+
+    fn to_html(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "<!DOCTYPE html>").unwrap();
+        writeln!(s, "<html>").unwrap();
+        writeln!(
+            s,
+            "<head><meta charset=\"utf-8\"><title>Everybody Codes 2025 progress</title></head>"
+        )
+        .unwrap();
+        writeln!(s, "<body>").unwrap();
+        writeln!(s, "<h1>Everybody Codes 2025 progress</h1>").unwrap();
+        writeln!(
+            s,
+            "<p>Solved {} of {} parts.</p>",
+            self.solved(),
+            self.rows.len()
+        )
+        .unwrap();
+        writeln!(s, "<table>").unwrap();
+        writeln!(s, "<tr><th>Quest</th><th>Solved</th><th>Tests</th></tr>").unwrap();
+        for (quest, solved, tests) in self.quests.iter() {
+            writeln!(
+                s,
+                "<tr><td>{quest}</td><td>{solved}</td><td>{tests}</td></tr>"
+            )
+            .unwrap();
+        }
+        writeln!(s, "</table>").unwrap();
+        writeln!(s, "<table>").unwrap();
+        writeln!(
+            s,
+            "<tr><th>Quest</th><th>Part</th><th>Status</th><th>Answer</th><th>Run time</th></tr>"
+        )
+        .unwrap();
+        for row in self.rows.iter() {
+            writeln!(
+                s,
+                "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                row.status.label().replace(' ', "-"),
+                row.quest,
+                row.part,
+                row.status.label(),
+                escape_html(row.answer.as_deref().unwrap_or("-")),
+                format_time(row.time),
+            )
+            .unwrap();
+        }
+        writeln!(s, "</table>").unwrap();
+        writeln!(s, "</body>").unwrap();
+        writeln!(s, "</html>").unwrap();
+        s
+    }
+}
+
+fn format_time(time: Option<Duration>) -> String {
+    time.map(|t| format!("{t:.2?}"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn escape_html(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Part;
+    use std::path::PathBuf;
+
+    fn sample_report() -> Report {
+        let quests = vec![Quest {
+            number: 1,
+            parts: (1..=3)
+                .map(|number| Part {
+                    number,
+                    input_file: PathBuf::from("does-not-exist.txt"),
+                })
+                .collect(),
+            test_count: 3,
+        }];
+        let answers = BTreeMap::from([((1, 1), "Fyrryn".to_string())]);
+        let benchmarks = BTreeMap::from([((1, 1), Duration::from_micros(12))]);
+        Report::new(&quests, &answers, &benchmarks)
+    }
+
+    #[test]
+    fn test_markdown() {
+        let result = sample_report().render(Format::Markdown);
+        assert!(result.contains("Solved 1 of 3 parts."));
+        assert!(result.contains("| 1 | 1 | 3 |"));
+        assert!(result.contains("| 1 | 1 | solved | Fyrryn | 12.00µs |"));
+        assert!(result.contains("| 1 | 2 | no input | - | - |"));
+    }
+
+    #[test]
+    fn test_html() {
+        let result = sample_report().render(Format::Html);
+        assert!(result.contains("<p>Solved 1 of 3 parts.</p>"));
+        assert!(result.contains("<tr class=\"no-input\"><td>1</td><td>3</td>"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}